@@ -0,0 +1,189 @@
+use chrono::{Days, Local, NaiveDate};
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::ListItem,
+};
+
+use crate::{
+    project::Project,
+    task::{Task, TASK_STATUS_DONE, TASK_STATUS_ON_GOING, TASK_STATUS_UP_NEXT},
+    App,
+};
+
+// Tasks to work on today from every project: the OnGoing ones, the UpNext ones
+// with the highest priority and the ones due soon (or overdue)
+pub struct Agenda;
+
+// Tasks due within these days are shown
+static DUE_SOON_DAYS: u64 = 3;
+// Priority of the UpNext tasks shown (1: "!!!")
+static HIGH_PRIORITY: u8 = 1;
+
+impl Agenda {
+    // Order of the groups in the agenda, None when the task is not shown
+    fn get_group(task: &Task, today: NaiveDate) -> Option<usize> {
+        if task.status == TASK_STATUS_ON_GOING {
+            return Some(0);
+        }
+
+        if task.status == TASK_STATUS_UP_NEXT && task.priority == HIGH_PRIORITY {
+            return Some(1);
+        }
+
+        let due_soon = today + Days::new(DUE_SOON_DAYS);
+
+        match task.due_date {
+            Some(due_date) if task.status != TASK_STATUS_DONE && due_date <= due_soon => Some(2),
+            _ => None,
+        }
+    }
+
+    // Project and task index of the tasks shown, in the order of the agenda
+    pub fn get_positions(projects: &[Project], today: NaiveDate) -> Vec<(usize, usize)> {
+        let mut positions: Vec<(usize, usize, usize)> = projects
+            .iter()
+            .enumerate()
+            .flat_map(|(p, project)| {
+                project
+                    .tasks
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(t, task)| Some((Agenda::get_group(task, today)?, p, t)))
+            })
+            .collect();
+
+        // Stable sort, the tasks of a group keep the order of the board unless they are due
+        positions.sort_by_key(|(group, p, t)| {
+            (
+                *group,
+                projects[*p].tasks[*t].due_date.unwrap_or(NaiveDate::MAX),
+            )
+        });
+
+        positions.into_iter().map(|(_, p, t)| (p, t)).collect()
+    }
+
+    pub fn load_items(app: &mut App, items: &mut Vec<ListItem>) {
+        let open_uids = Task::get_open_uids(&app.projects);
+        let today = Local::now().date_naive();
+
+        items.clear();
+
+        for (p, t) in Agenda::get_positions(&app.projects, today) {
+            let project = &app.projects[p];
+
+            let repr = [
+                vec![Span::styled(
+                    format!("{}: ", project.title),
+                    Style::new().fg(Color::DarkGray),
+                )],
+                Task::get_repr(
+                    &project.tasks[t],
+                    &open_uids,
+                    app.config.ui.show_task_age,
+                    today,
+                ),
+            ]
+            .concat();
+
+            items.push(ListItem::from(Line::from(repr)))
+        }
+
+        // The task could have left the agenda after the last change
+        let index = app
+            .selected_agenda_index
+            .selected()
+            .unwrap_or(0)
+            .min(items.len().saturating_sub(1));
+        app.selected_agenda_index.select(Some(index))
+    }
+
+    // Select the project and the task of the agenda row, so the task actions apply to it
+    pub fn select_task(app: &mut App) {
+        let Some(index) = app.selected_agenda_index.selected() else {
+            return;
+        };
+
+        if let Some(&(p, t)) =
+            Agenda::get_positions(&app.projects, Local::now().date_naive()).get(index)
+        {
+            app.selected_project_index.select(Some(p));
+            app.selected_task_index.select(Some(t));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_helpers::date;
+
+    fn task(title: &str, status: &str, priority: u8, due_date: Option<&str>) -> Task {
+        Task {
+            title: title.to_string(),
+            status: status.to_string(),
+            priority,
+            due_date: due_date.map(date),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn groups() {
+        let today = date("2026-10-15");
+
+        for (task, group) in [
+            (task("A", TASK_STATUS_ON_GOING, 0, None), Some(0)),
+            (
+                task("B", TASK_STATUS_ON_GOING, 3, Some("2026-10-10")),
+                Some(0),
+            ),
+            (task("C", TASK_STATUS_UP_NEXT, 1, None), Some(1)),
+            (task("D", TASK_STATUS_UP_NEXT, 2, None), None),
+            (
+                task("E", TASK_STATUS_UP_NEXT, 0, Some("2026-10-18")),
+                Some(2),
+            ),
+            (task("F", TASK_STATUS_UP_NEXT, 0, Some("2026-10-19")), None),
+            (
+                task("G", TASK_STATUS_UP_NEXT, 3, Some("2026-10-01")),
+                Some(2),
+            ),
+            (task("H", TASK_STATUS_DONE, 1, Some("2026-10-15")), None),
+        ] {
+            assert_eq!(Agenda::get_group(&task, today), group, "{}", task.title);
+        }
+    }
+
+    #[test]
+    fn positions_sorted_by_group_and_due_date() {
+        let today = date("2026-10-15");
+        let projects = vec![
+            Project {
+                id: None,
+                title: "P".to_string(),
+                tasks: vec![
+                    task("Due later", TASK_STATUS_UP_NEXT, 0, Some("2026-10-17")),
+                    task("High", TASK_STATUS_UP_NEXT, 1, None),
+                    task("Working", TASK_STATUS_ON_GOING, 0, None),
+                ],
+            },
+            Project {
+                id: None,
+                title: "Q".to_string(),
+                tasks: vec![
+                    task("Overdue", TASK_STATUS_UP_NEXT, 0, Some("2026-10-14")),
+                    task("Not shown", TASK_STATUS_UP_NEXT, 0, None),
+                    task("Working due", TASK_STATUS_ON_GOING, 0, Some("2026-10-20")),
+                    task("Also working", TASK_STATUS_ON_GOING, 0, None),
+                ],
+            },
+        ];
+
+        assert_eq!(
+            Agenda::get_positions(&projects, today),
+            vec![(1, 2), (0, 2), (1, 3), (0, 1), (1, 0), (0, 0)]
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+use crate::{
+    project::Project,
+    task::{Task, TASK_STATUSES},
+    App,
+};
+
+// Month grid of the tasks by due date, moved by day, week or month from the selected day
+pub struct Calendar;
+
+impl Calendar {
+    // Weeks of the month of the date, from monday, with None outside of the month
+    pub fn get_weeks(date: NaiveDate) -> Vec<[Option<NaiveDate>; 7]> {
+        let first_day = date.with_day(1).unwrap();
+        let mut weeks: Vec<[Option<NaiveDate>; 7]> = vec![];
+        let mut week = [None; 7];
+
+        for day in first_day
+            .iter_days()
+            .take_while(|d| d.month() == date.month())
+        {
+            let weekday = day.weekday().num_days_from_monday() as usize;
+            week[weekday] = Some(day);
+
+            if weekday == 6 {
+                weeks.push(week);
+                week = [None; 7];
+            }
+        }
+
+        if week.iter().any(|d| d.is_some()) {
+            weeks.push(week);
+        }
+
+        weeks
+    }
+
+    // Project and task of the tasks due on the day
+    pub fn get_tasks(projects: &[Project], day: NaiveDate) -> Vec<(&Project, &Task)> {
+        projects
+            .iter()
+            .flat_map(|p| p.tasks.iter().map(move |t| (p, t)))
+            .filter(|(_, t)| t.due_date == Some(day))
+            .collect()
+    }
+
+    // Tasks due on the day of each status, in the order of `TASK_STATUSES`
+    pub fn get_status_counts(projects: &[Project], day: NaiveDate) -> Vec<(&'static str, usize)> {
+        let tasks = Calendar::get_tasks(projects, day);
+
+        TASK_STATUSES
+            .into_iter()
+            .map(|status| {
+                (
+                    status,
+                    tasks.iter().filter(|(_, t)| t.status == status).count(),
+                )
+            })
+            .collect()
+    }
+
+    // The same date when out of range
+    fn get_moved_days(date: NaiveDate, days: i64) -> NaiveDate {
+        let days_delta = Days::new(days.unsigned_abs());
+
+        let moved = if days < 0 {
+            date.checked_sub_days(days_delta)
+        } else {
+            date.checked_add_days(days_delta)
+        };
+
+        moved.unwrap_or(date)
+    }
+
+    // The day is kept, or the last one of shorter months
+    fn get_moved_months(date: NaiveDate, months: i32) -> NaiveDate {
+        let months_delta = Months::new(months.unsigned_abs());
+
+        let moved = if months < 0 {
+            date.checked_sub_months(months_delta)
+        } else {
+            date.checked_add_months(months_delta)
+        };
+
+        moved.unwrap_or(date)
+    }
+
+    pub fn move_days(app: &mut App, days: i64) {
+        app.calendar_date = Calendar::get_moved_days(app.calendar_date, days)
+    }
+
+    pub fn move_months(app: &mut App, months: i32) {
+        app.calendar_date = Calendar::get_moved_months(app.calendar_date, months)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_helpers::date;
+
+    #[test]
+    fn weeks_of_month_starting_on_sunday() {
+        // February 2026 starts on sunday and ends on saturday
+        let weeks = Calendar::get_weeks(date("2026-02-14"));
+
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(weeks[0][..6], [None; 6]);
+        assert_eq!(weeks[0][6], Some(date("2026-02-01")));
+        assert_eq!(weeks[1][0], Some(date("2026-02-02")));
+        assert_eq!(weeks[4][5], Some(date("2026-02-28")));
+        assert_eq!(weeks[4][6], None);
+    }
+
+    #[test]
+    fn weeks_of_six_weeks_month() {
+        // March 2026 starts on sunday and has 31 days
+        let weeks = Calendar::get_weeks(date("2026-03-01"));
+
+        assert_eq!(weeks.len(), 6);
+        assert_eq!(weeks[0][6], Some(date("2026-03-01")));
+        assert_eq!(
+            weeks[5][..2],
+            [Some(date("2026-03-30")), Some(date("2026-03-31"))]
+        );
+        assert_eq!(weeks[5][2..], [None; 5]);
+        assert_eq!(weeks.iter().flatten().flatten().count(), 31);
+    }
+
+    #[test]
+    fn moved_dates() {
+        assert_eq!(
+            Calendar::get_moved_months(date("2026-01-31"), 1),
+            date("2026-02-28")
+        );
+        assert_eq!(
+            Calendar::get_moved_months(date("2028-01-31"), 1),
+            date("2028-02-29")
+        );
+        assert_eq!(
+            Calendar::get_moved_months(date("2026-03-31"), -1),
+            date("2026-02-28")
+        );
+        assert_eq!(
+            Calendar::get_moved_days(date("2026-12-29"), 7),
+            date("2027-01-05")
+        );
+        assert_eq!(
+            Calendar::get_moved_days(date("2026-03-02"), -7),
+            date("2026-02-23")
+        );
+        assert_eq!(Calendar::get_moved_days(NaiveDate::MAX, 1), NaiveDate::MAX);
+    }
+}
@@ -1,20 +1,401 @@
-use std::{env, process::exit};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+use chrono::TimeDelta;
+use serde_json::to_string_pretty;
+use similar::TextDiff;
+
+use crate::{
+    config::{Backend, Config},
+    interop::{
+        csv::Csv, ics::Ics, markdown::Markdown, taskwarrior::Taskwarrior, todotxt::TodoTxt,
+        trello::Trello, Interop,
+    },
+    json::Json,
+    project::Project,
+    report::Report,
+    store::Store,
+    task::Task,
+    util::Util,
+};
 
 pub struct Cli;
 
+// Options without a value
+static BOOL_FLAGS: [&str; 2] = ["--dry-run", "--force"];
+
 impl Cli {
+    fn exit_with_error(message: &str) -> ! {
+        eprint!("{} - ERROR: {}", env!("CARGO_PKG_NAME"), message);
+        exit(1)
+    }
+
+    // Accept both `--flag value` and `--flag=value`
+    fn get_flag_value(
+        flag: &str,
+        inline_value: Option<&str>,
+        args: &mut impl Iterator<Item = String>,
+    ) -> String {
+        let value = match inline_value {
+            Some(v) => Some(v.to_string()),
+            None => args.next(),
+        };
+
+        match value {
+            Some(v) if !v.is_empty() => v,
+            _ => Cli::exit_with_error(&format!("Missing value for {flag}")),
+        }
+    }
+
+    // Value of an option inside the arguments of a command
+    fn get_option(args: &[String], name: &str) -> Option<String> {
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if arg == name {
+                return iter.next().cloned();
+            }
+
+            if let Some(value) = arg.strip_prefix(&format!("{name}=")) {
+                return Some(value.to_string());
+            }
+        }
+
+        None
+    }
+
+    fn has_flag(args: &[String], name: &str) -> bool {
+        args.iter().any(|a| a == name)
+    }
+
+    // Arguments that are neither options nor their values
+    fn get_positionals(args: &[String]) -> Vec<String> {
+        let mut positionals: Vec<String> = vec![];
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if !arg.starts_with("--") {
+                positionals.push(arg.clone());
+                continue;
+            }
+
+            // Skip the value of the option
+            if !arg.contains('=') && !BOOL_FLAGS.contains(&arg.as_str()) {
+                iter.next();
+            }
+        }
+
+        positionals
+    }
+
+    fn get_backend_option(args: &[String], name: &str) -> Option<Backend> {
+        let value = Cli::get_option(args, name)?;
+
+        match Backend::from_name(&value) {
+            Some(backend) => Some(backend),
+            None => Cli::exit_with_error(&format!("Unknown storage backend \"{value}\"")),
+        }
+    }
+
+    // basilk convert --to <json|sqlite|directory> [--from <json|sqlite|directory>] [--force]
+    fn convert(args: &[String]) {
+        let storage = Config::read().storage;
+        let from = Cli::get_backend_option(args, "--from").unwrap_or(storage.backend);
+        let to = match Cli::get_backend_option(args, "--to") {
+            Some(to) => to,
+            None => Cli::exit_with_error("Missing value for --to"),
+        };
+
+        if from == to {
+            Cli::exit_with_error("--from and --to must be different backends");
+        }
+
+        match storage.convert(&from, &to, Cli::has_flag(args, "--force")) {
+            Ok(count) => println!(
+                "Converted {count} projects from {} to {}. Set `backend = \"{}\"` in the [storage] section of the config to use it",
+                from.get_name(),
+                to.get_name(),
+                to.get_name()
+            ),
+            Err(e) => Cli::exit_with_error(&e.to_string()),
+        }
+    }
+
+    // Open the configured storage, applying the pending migrations
+    fn open_store() -> Box<dyn Store> {
+        let store = match Config::read().storage.open() {
+            Ok(store) => store,
+            Err(e) => Cli::exit_with_error(&e.to_string()),
+        };
+
+        if let Err(e) = store.check() {
+            Cli::exit_with_error(&e.to_string())
+        }
+
+        store
+    }
+
+    // Only the project with the given title, if the `--project` option is set
+    fn filter_projects(args: &[String], projects: Vec<Project>) -> Vec<Project> {
+        let Some(title) = Cli::get_option(args, "--project") else {
+            return projects;
+        };
+
+        let filtered: Vec<Project> = projects.into_iter().filter(|p| p.title == title).collect();
+
+        if filtered.is_empty() {
+            Cli::exit_with_error(&format!("Project \"{title}\" not found"));
+        }
+
+        filtered
+    }
+
+    // Print the content, or write it into the file of the `--output` option
+    fn write_output(args: &[String], content: &str) {
+        match Cli::get_option(args, "--output") {
+            Some(path) => {
+                if let Err(e) = fs::write(&path, content) {
+                    Cli::exit_with_error(&e.to_string())
+                }
+            }
+            None => print!("{content}"),
+        }
+    }
+
+    // basilk export --format <markdown|csv|todotxt|ics|taskwarrior> [--project <title>] [--output <file>]
+    fn export(args: &[String]) {
+        let store = Cli::open_store();
+        let mut projects = store.load();
+        let format = Cli::get_option(args, "--format");
+
+        // The exported tasks are identified by their uid
+        if matches!(format.as_deref(), Some("ics") | Some("taskwarrior")) {
+            Interop::create_uids(store.as_ref(), &mut projects)
+        }
+
+        let projects = Cli::filter_projects(args, projects);
+
+        let content = match format.as_deref() {
+            Some("markdown") | Some("md") => Markdown::export(&projects),
+            Some("csv") => match Csv::export(&projects) {
+                Ok(content) => content,
+                Err(e) => Cli::exit_with_error(&e.to_string()),
+            },
+            Some("todotxt") | Some("todo.txt") => TodoTxt::export(&projects),
+            Some("ics") => Ics::export(&projects),
+            Some("taskwarrior") => Taskwarrior::export(&projects),
+            Some(format) => Cli::exit_with_error(&format!("Unknown export format \"{format}\"")),
+            None => Cli::exit_with_error("Missing value for --format"),
+        };
+
+        Cli::write_output(args, &content)
+    }
+
+    // basilk import <markdown|csv|todotxt|ics|taskwarrior|trello> <file> [--project <title>] [--map <mapping>] [--dry-run]
+    fn import(args: &[String]) {
+        let positionals = Cli::get_positionals(args);
+        let (Some(format), Some(file)) = (positionals.first(), positionals.get(1)) else {
+            Cli::exit_with_error("Usage: import <format> <file> [--project <title>] [--dry-run]")
+        };
+
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => Cli::exit_with_error(&format!("Unable to read \"{file}\": {e}")),
+        };
+
+        // Tasks outside of a project go into a project named as the file
+        let default_title = Path::new(file)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or(file.to_string());
+
+        let result = match format.as_str() {
+            "markdown" | "md" => Ok(Markdown::import(&content, &default_title)),
+            "csv" => Csv::parse_mapping(&Cli::get_option(args, "--map").unwrap_or_default())
+                .and_then(|mapping| Csv::import(&content, &default_title, &mapping)),
+            "todotxt" | "todo.txt" => TodoTxt::import(&content, &default_title),
+            "ics" => Ics::import(&content, &default_title),
+            "taskwarrior" => Taskwarrior::import(&content, &default_title),
+            "trello" | "kanban" => {
+                Trello::parse_mapping(&Cli::get_option(args, "--map").unwrap_or_default())
+                    .and_then(|mapping| Trello::import(&content, &mapping))
+            }
+            _ => Cli::exit_with_error(&format!("Unknown import format \"{format}\"")),
+        };
+
+        let mut imported = match result {
+            Ok(imported) => imported,
+            Err(e) => Cli::exit_with_error(&format!("Unable to import \"{file}\"\n{e}")),
+        };
+
+        let store = Cli::open_store();
+        let projects = store.load();
+
+        // Each board is a new project, the other formats are merged by title
+        match Cli::get_option(args, "--project") {
+            Some(title) => imported = Interop::into_project(imported, &title),
+            None if matches!(format.as_str(), "trello" | "kanban") => {
+                imported = Interop::rename_existing(&projects, imported)
+            }
+            None => {}
+        }
+
+        let (projects, summary) = Interop::merge(&projects, imported);
+
+        println!("{}", summary.join("\n"));
+
+        if Cli::has_flag(args, "--dry-run") {
+            println!("Dry run, nothing was imported");
+            return;
+        }
+
+        store.save_all(&projects);
+    }
+
+    // basilk report --html <file> [--project <title>]
+    fn report(args: &[String]) {
+        let Some(file) = Cli::get_option(args, "--html") else {
+            Cli::exit_with_error("Usage: report --html <file> [--project <title>]")
+        };
+
+        let projects = Cli::filter_projects(args, Cli::open_store().load());
+
+        match fs::write(&file, Report::html(&projects)) {
+            Ok(_) => println!("Report written to \"{file}\""),
+            Err(e) => Cli::exit_with_error(&format!("Unable to write \"{file}\": {e}")),
+        }
+    }
+
+    // basilk time report [--project <title>]
+    fn time(args: &[String]) {
+        let positionals = Cli::get_positionals(args);
+
+        if positionals.first().map(|p| p.as_str()) != Some("report") {
+            Cli::exit_with_error("Usage: time report [--project <title>]")
+        }
+
+        let projects = Cli::filter_projects(args, Cli::open_store().load());
+        let mut lines: Vec<(String, String)> = vec![];
+
+        for project in projects.iter() {
+            let total = Project::get_tracked_time(project);
+
+            if total.is_zero() {
+                continue;
+            }
+
+            lines.push((project.title.clone(), Util::get_clock_repr(total)));
+
+            for task in project.tasks.iter().filter(|t| !t.time_entries.is_empty()) {
+                let running = if Task::is_timer_running(task) {
+                    " (running)"
+                } else {
+                    ""
+                };
+
+                lines.push((
+                    format!("  {}", task.title),
+                    format!(
+                        "{}{running}",
+                        Util::get_clock_repr(Task::get_tracked_time(task))
+                    ),
+                ));
+            }
+        }
+
+        let total: TimeDelta = projects.iter().map(Project::get_tracked_time).sum();
+        lines.push(("Total".to_string(), Util::get_clock_repr(total)));
+
+        let width = lines
+            .iter()
+            .map(|(l, _)| l.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        for (label, time) in lines {
+            println!("{label:<width$}  {time}");
+        }
+    }
+
+    // basilk migrate [--dry-run]
+    fn migrate(args: &[String]) {
+        let store = match Config::read().storage.open() {
+            Ok(store) => store,
+            Err(e) => Cli::exit_with_error(&e.to_string()),
+        };
+
+        if !Cli::has_flag(args, "--dry-run") {
+            match store.check() {
+                Ok(true) => println!("Migrations applied"),
+                Ok(false) => println!("Nothing to migrate"),
+                Err(e) => Cli::exit_with_error(&e.to_string()),
+            }
+
+            return;
+        }
+
+        match store.get_pending_migration() {
+            Ok(Some((json, new_json))) => {
+                let json = to_string_pretty(&json).unwrap() + "\n";
+                let new_json = to_string_pretty(&new_json).unwrap() + "\n";
+
+                print!(
+                    "{}",
+                    TextDiff::from_lines(&json, &new_json)
+                        .unified_diff()
+                        .header("current", "migrated")
+                );
+            }
+            Ok(None) => println!("Nothing to migrate"),
+            Err(e) => Cli::exit_with_error(&e.to_string()),
+        }
+    }
+
     pub fn read() {
         // If you use `cargo run main.rs`, skip must be 2
         let mut args = env::args().skip(1);
+        let mut command: Vec<String> = vec![];
 
-        match args.next() {
-            Some(arg) => {
-                if arg == "--version" {
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value)),
+                None => (arg.clone(), None),
+            };
+
+            match flag.as_str() {
+                "--version" => {
                     print!(env!("CARGO_PKG_VERSION"));
                     exit(0)
                 }
+                "--data-dir" => {
+                    let value = Cli::get_flag_value(&flag, inline_value, &mut args);
+                    Json::set_dir_path(PathBuf::from(value))
+                }
+                "--config" => {
+                    let value = Cli::get_flag_value(&flag, inline_value, &mut args);
+                    Config::set_config_path(PathBuf::from(value))
+                }
+                _ => command.push(arg),
             }
-            None => (),
         }
+
+        let Some(name) = command.first() else {
+            return;
+        };
+
+        match name.as_str() {
+            "convert" => Cli::convert(&command[1..]),
+            "migrate" => Cli::migrate(&command[1..]),
+            "export" => Cli::export(&command[1..]),
+            "import" => Cli::import(&command[1..]),
+            "report" => Cli::report(&command[1..]),
+            "time" => Cli::time(&command[1..]),
+            _ => Cli::exit_with_error(&format!("Unknown argument \"{name}\"")),
+        }
+
+        exit(0)
     }
 }
@@ -1,8 +1,10 @@
 use std::{
+    env,
     fs::{self, File},
     io::Write,
     path::PathBuf,
     process::exit,
+    sync::Mutex,
 };
 
 use serde::{Deserialize, Serialize};
@@ -12,27 +14,138 @@ use crate::json::Json;
 #[derive(Deserialize, Serialize)]
 pub struct ConfigToml {
     pub ui: Ui,
+    #[serde(default)]
+    pub storage: Storage,
+    #[serde(default)]
+    pub export: Export,
+    #[serde(default)]
+    pub time: Time,
+    #[serde(default)]
+    pub focus: Focus,
 }
 
 #[derive(Deserialize, Serialize)]
 pub struct Ui {
     pub show_help: bool,
+    // Time since the creation of each task, next to its title
+    #[serde(default)]
+    pub show_task_age: bool,
+    // Click, double click and scroll wheel in the lists and modals
+    #[serde(default)]
+    pub mouse: bool,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct Storage {
+    pub backend: Backend,
+    // Format of the files used by the "directory" backend
+    #[serde(default)]
+    pub format: FileFormat,
+    // Indented json file with sorted keys, used by the "json" backend
+    #[serde(default)]
+    pub pretty_json: bool,
+}
+
+// Destination of the export from the app
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct Export {
+    // Command receiving the export on stdin (i.e. "wl-copy", "pbcopy", "xclip -selection clipboard")
+    pub clipboard_command: Option<String>,
+    // File written when there is no clipboard command
+    pub file: String,
+}
+
+impl Default for Export {
+    fn default() -> Self {
+        Export {
+            clipboard_command: None,
+            file: format!("{}.md", env!("CARGO_PKG_NAME")),
+        }
+    }
+}
+
+// Time tracking of the tasks
+#[derive(Deserialize, Serialize, Default)]
+pub struct Time {
+    // Start the timer of a task when it's moved to OnGoing
+    #[serde(default)]
+    pub auto_start: bool,
+}
+
+// Countdowns of the focus mode
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct Focus {
+    pub work_minutes: u32,
+    pub break_minutes: u32,
+}
+
+impl Default for Focus {
+    fn default() -> Self {
+        Focus {
+            work_minutes: 25,
+            break_minutes: 5,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    Json,
+    Sqlite,
+    Directory,
+}
+
+#[derive(Deserialize, Serialize, Default, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum FileFormat {
+    #[default]
+    Json,
+    Toml,
 }
 
 pub struct Config;
 
 static CONFIG_FILE_NAME: &str = "config";
+static CONFIG_PATH_ENV: &str = "BASILK_CONFIG";
+// Config file passed with the `--config` flag
+static CONFIG_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
 
 impl Config {
     fn get_default() -> ConfigToml {
         ConfigToml {
-            ui: Ui { show_help: true },
+            ui: Ui {
+                show_help: true,
+                show_task_age: false,
+                mouse: false,
+            },
+            storage: Storage::default(),
+            export: Export::default(),
+            time: Time::default(),
+            focus: Focus::default(),
         }
     }
 
+    pub fn set_config_path(path: PathBuf) {
+        *CONFIG_PATH.lock().unwrap() = Some(path);
+    }
+
     fn get_config_path() -> PathBuf {
+        // Priority: `--config` flag, `BASILK_CONFIG` env variable, default directory.
+        // The data directory is not used here so the config can live apart from the data
+        if let Some(path) = CONFIG_PATH.lock().unwrap().clone() {
+            return path;
+        }
+
+        if let Some(path) = env::var_os(CONFIG_PATH_ENV).filter(|p| !p.is_empty()) {
+            return PathBuf::from(path);
+        }
+
         let mut path = PathBuf::new();
-        path.push(Json::get_dir_path().as_path());
+        path.push(Json::get_default_dir_path().as_path());
         path.push(format!("{CONFIG_FILE_NAME}.toml"));
 
         return path;
@@ -46,6 +159,10 @@ impl Config {
             Err(_) => {
                 let default_config = toml::to_string(&Config::get_default()).unwrap();
 
+                if let Some(parent) = path.parent() {
+                    let _ = fs::create_dir_all(parent);
+                }
+
                 let mut file = File::create(&path).unwrap();
                 let _ = file.write_all(default_config.as_bytes());
 
@@ -0,0 +1,262 @@
+use std::{collections::HashSet, error::Error, fs, path::PathBuf};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    config::FileFormat,
+    json::Json,
+    migration::{Migration, JSON_VERSIONS},
+    project::Project,
+    store::Store,
+};
+
+// Each project is saved in its own pretty-printed file to have readable git diffs
+pub struct Directory {
+    format: FileFormat,
+}
+
+static DIR_PROJECTS_NAME: &str = "projects";
+static INDEX_FILE_NAME: &str = "index";
+
+#[derive(Deserialize, Serialize)]
+struct Index {
+    // Schema version of the project files (cf. JSON_VERSIONS)
+    version: String,
+    // Project file names, in the same order shown in the app
+    projects: Vec<String>,
+}
+
+impl Directory {
+    pub fn open(format: FileFormat) -> Result<Directory, Box<dyn Error>> {
+        fs::create_dir_all(Directory::get_dir_path())?;
+
+        Ok(Directory { format })
+    }
+
+    fn get_dir_path() -> PathBuf {
+        let mut path = PathBuf::new();
+        path.push(Json::get_dir_path().as_path());
+        path.push(DIR_PROJECTS_NAME);
+
+        path
+    }
+
+    fn get_extension(&self) -> &str {
+        match self.format {
+            FileFormat::Json => "json",
+            FileFormat::Toml => "toml",
+        }
+    }
+
+    fn get_index_file_name(&self) -> String {
+        format!("{INDEX_FILE_NAME}.{}", self.get_extension())
+    }
+
+    fn get_file_path(file_name: &str) -> PathBuf {
+        let mut path = Directory::get_dir_path();
+        path.push(file_name);
+
+        path
+    }
+
+    // File name from the project title (i.e. "My Project!" => "my-project.json"),
+    // a counter is appended when two projects have the same title
+    fn get_file_name(&self, title: &str, used_names: &HashSet<String>) -> String {
+        let slug = title
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<&str>>()
+            .join("-");
+
+        let slug = if slug.is_empty() {
+            "project".to_string()
+        } else {
+            slug
+        };
+
+        let mut file_name = format!("{slug}.{}", self.get_extension());
+        let mut counter = 2;
+
+        while used_names.contains(&file_name) || file_name == self.get_index_file_name() {
+            file_name = format!("{slug}-{counter}.{}", self.get_extension());
+            counter += 1;
+        }
+
+        file_name
+    }
+
+    fn serialize<T: Serialize>(&self, value: &T) -> String {
+        match self.format {
+            FileFormat::Json => format!("{}\n", serde_json::to_string_pretty(value).unwrap()),
+            FileFormat::Toml => toml::to_string_pretty(value).unwrap(),
+        }
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, raw: &str) -> Result<T, Box<dyn Error>> {
+        match self.format {
+            FileFormat::Json => Ok(serde_json::from_str(raw)?),
+            FileFormat::Toml => Ok(toml::from_str(raw)?),
+        }
+    }
+
+    // Write only the changed files, so untouched projects keep their modification time
+    fn write_file(file_name: &str, content: &str) {
+        let path = Directory::get_file_path(file_name);
+
+        if fs::read_to_string(&path).is_ok_and(|c| c == content) {
+            return;
+        }
+
+        fs::write(path, content).unwrap();
+    }
+
+    // The content of the file, the error names it (i.e. a file left with git conflicts)
+    fn read_file<T: DeserializeOwned>(&self, file_name: &str) -> Result<T, Box<dyn Error>> {
+        let path = Directory::get_file_path(file_name);
+        let raw = fs::read_to_string(&path)
+            .map_err(|e| format!("Unable to read \"{}\": {e}", path.display()))?;
+
+        self.deserialize::<T>(&raw)
+            .map_err(|e| format!("Invalid file \"{}\": {e}", path.display()).into())
+    }
+
+    // None when there is no index yet
+    fn read_index(&self) -> Result<Option<Index>, Box<dyn Error>> {
+        let file_name = self.get_index_file_name();
+
+        if !Directory::get_file_path(&file_name).is_file() {
+            return Ok(None);
+        }
+
+        Ok(Some(self.read_file::<Index>(&file_name)?))
+    }
+
+    fn write_index(&self, version: &str, projects: Vec<String>) {
+        let index = Index {
+            version: version.to_string(),
+            projects,
+        };
+
+        Directory::write_file(&self.get_index_file_name(), &self.serialize(&index));
+    }
+
+    // All project files: first the ones listed in the index, then the ones not indexed yet
+    // (i.e. added by a git merge) sorted by name
+    fn get_file_names(&self, index: &Index) -> Vec<String> {
+        let mut file_names: Vec<String> = index
+            .projects
+            .iter()
+            .filter(|name| Directory::get_file_path(name).is_file())
+            .cloned()
+            .collect();
+
+        let mut not_indexed: Vec<String> = fs::read_dir(Directory::get_dir_path())
+            .unwrap()
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| {
+                name.ends_with(&format!(".{}", self.get_extension()))
+                    && *name != self.get_index_file_name()
+                    && !file_names.contains(name)
+            })
+            .collect();
+        not_indexed.sort();
+
+        file_names.append(&mut not_indexed);
+
+        file_names
+    }
+
+    fn read_projects<T: DeserializeOwned>(&self, index: &Index) -> Result<Vec<T>, Box<dyn Error>> {
+        self.get_file_names(index)
+            .iter()
+            .map(|name| self.read_file::<T>(name))
+            .collect()
+    }
+
+    fn get_empty_index() -> Index {
+        Index {
+            version: JSON_VERSIONS.last().unwrap().to_string(),
+            projects: vec![],
+        }
+    }
+}
+
+impl Store for Directory {
+    fn check(&self) -> Result<bool, Box<dyn Error>> {
+        let Some(index) = self.read_index()? else {
+            let index = Directory::get_empty_index();
+            self.write_index(&index.version, index.projects);
+
+            return Ok(false);
+        };
+
+        let Some((_json, new_json)) = self.get_pending_migration()? else {
+            // The files are read on every load, an invalid one is reported now
+            self.read_projects::<Project>(&index)?;
+
+            return Ok(false);
+        };
+
+        // The project files are rewritten only once, with the result of all the migrations
+        let projects = serde_json::from_value::<Vec<Project>>(new_json)?;
+        self.save_all(&projects);
+
+        Ok(true)
+    }
+
+    fn get_pending_migration(&self) -> Result<Option<(Value, Value)>, Box<dyn Error>> {
+        let Some(index) = self.read_index()? else {
+            return Ok(None);
+        };
+
+        let json = self.read_projects::<Value>(&index)?;
+
+        if index.version == *JSON_VERSIONS.last().unwrap() || json.is_empty() {
+            return Ok(None);
+        }
+
+        let (_version, new_json) = Migration::migrate(&index.version, Value::from(json.clone()))?;
+
+        Ok(Some((Value::from(json), new_json)))
+    }
+
+    // The files are validated by `check`
+    fn load(&self) -> Vec<Project> {
+        let index = self
+            .read_index()
+            .unwrap()
+            .unwrap_or(Directory::get_empty_index());
+
+        self.read_projects::<Project>(&index).unwrap()
+    }
+
+    fn save_all(&self, projects: &[Project]) {
+        let mut file_names: Vec<String> = vec![];
+        let mut used_names: HashSet<String> = HashSet::new();
+
+        for project in projects {
+            let file_name = self.get_file_name(&project.title, &used_names);
+
+            Directory::write_file(&file_name, &self.serialize(project));
+
+            used_names.insert(file_name.clone());
+            file_names.push(file_name);
+        }
+
+        // Remove the files of deleted (or renamed) projects
+        let index = self
+            .read_index()
+            .unwrap()
+            .unwrap_or(Directory::get_empty_index());
+
+        for file_name in self.get_file_names(&index) {
+            if !used_names.contains(&file_name) {
+                fs::remove_file(Directory::get_file_path(&file_name)).unwrap();
+            }
+        }
+
+        self.write_index(JSON_VERSIONS.last().unwrap(), file_names);
+    }
+}
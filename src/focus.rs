@@ -0,0 +1,42 @@
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::{config, util::Util};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum FocusPhase {
+    Work,
+    Break,
+    // The break is over, waiting for a new session
+    Idle,
+}
+
+// Countdown of the focus mode on the selected task
+pub struct Focus {
+    pub phase: FocusPhase,
+    pub started_at: DateTime<Utc>,
+}
+
+impl Focus {
+    pub fn new(phase: FocusPhase) -> Focus {
+        Focus {
+            phase,
+            started_at: Util::get_now(),
+        }
+    }
+
+    pub fn get_duration(&self, config: &config::Focus) -> TimeDelta {
+        match self.phase {
+            FocusPhase::Work => TimeDelta::minutes(config.work_minutes.into()),
+            FocusPhase::Break => TimeDelta::minutes(config.break_minutes.into()),
+            FocusPhase::Idle => TimeDelta::zero(),
+        }
+    }
+
+    pub fn get_remaining(&self, config: &config::Focus) -> TimeDelta {
+        (self.get_duration(config) - (Util::get_now() - self.started_at)).max(TimeDelta::zero())
+    }
+
+    pub fn is_over(&self, config: &config::Focus) -> bool {
+        self.phase != FocusPhase::Idle && self.get_remaining(config).is_zero()
+    }
+}
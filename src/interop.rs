@@ -0,0 +1,183 @@
+use std::{
+    error::Error,
+    fs,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use crate::{config::Export, project::Project, store::Store, task::Task};
+
+pub mod csv;
+pub mod ics;
+pub mod markdown;
+pub mod taskwarrior;
+pub mod todotxt;
+pub mod trello;
+
+static BASIC_DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+// Conversions between the board and the formats used by other tools
+pub struct Interop;
+
+impl Interop {
+    // Send the exported content to the clipboard command, or to the file when no command is set.
+    // Return a description of where the content was sent
+    pub fn send(config: &Export, content: &str) -> Result<String, Box<dyn Error>> {
+        let Some(clipboard_command) = &config.clipboard_command else {
+            fs::write(&config.file, content)?;
+
+            return Ok(format!("Exported to {}", config.file));
+        };
+
+        // The command is not run through a shell, arguments are split on whitespaces
+        let mut words = clipboard_command.split_whitespace();
+        let program = words.next().ok_or("The clipboard command is empty")?;
+
+        let mut child = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        child
+            .stdin
+            .take()
+            .ok_or("Unable to write to the clipboard command")?
+            .write_all(content.as_bytes())?;
+
+        if !child.wait()?.success() {
+            return Err(format!("The clipboard command \"{clipboard_command}\" failed").into());
+        }
+
+        Ok("Exported to the clipboard".to_string())
+    }
+
+    // Dates in the basic format of iCalendar and Taskwarrior: "20240131T093000Z"
+    pub fn format_date(date: DateTime<Utc>) -> String {
+        date.format(BASIC_DATE_FORMAT).to_string()
+    }
+
+    pub fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+        NaiveDateTime::parse_from_str(value.trim(), BASIC_DATE_FORMAT)
+            .ok()
+            .map(|d| d.and_utc())
+    }
+
+    // Hash (FNV-1a) with the same value on every run, unlike the hasher of the std
+    pub fn get_hash(text: &str) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+
+        for byte in text.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+
+        hash
+    }
+
+    // Create the missing uids of the tasks and save them, so the ids of the exported
+    // tasks are the same on every export
+    pub fn create_uids(store: &dyn Store, projects: &mut [Project]) {
+        let mut is_created = false;
+
+        for task in projects.iter_mut().flat_map(|p| p.tasks.iter_mut()) {
+            if task.uid.is_none() {
+                Task::get_or_create_uid(task);
+                is_created = true;
+            }
+        }
+
+        if is_created {
+            store.save_all(projects)
+        }
+    }
+
+    // Add the task to the project with the given title, creating it when missing
+    pub fn add_task(projects: &mut Vec<Project>, project_title: &str, task: Task) {
+        match projects.iter_mut().find(|p| p.title == project_title) {
+            Some(project) => project.tasks.push(task),
+            None => projects.push(Project {
+                id: None,
+                title: project_title.to_string(),
+                tasks: vec![task],
+            }),
+        }
+    }
+
+    // Put all the imported tasks into a single project
+    pub fn into_project(imported: Vec<Project>, title: &str) -> Vec<Project> {
+        vec![Project {
+            id: None,
+            title: title.to_string(),
+            tasks: imported.into_iter().flat_map(|p| p.tasks).collect(),
+        }]
+    }
+
+    // Imported projects with the title of another one, existing or imported, get a number:
+    // "Board (2)"
+    pub fn rename_existing(projects: &[Project], imported: Vec<Project>) -> Vec<Project> {
+        let mut titles: Vec<String> = projects.iter().map(|p| p.title.clone()).collect();
+
+        imported
+            .into_iter()
+            .map(|project| {
+                let title = (1..)
+                    .map(|n| match n {
+                        1 => project.title.clone(),
+                        n => format!("{} ({n})", project.title),
+                    })
+                    .find(|t| !titles.contains(t))
+                    .unwrap();
+                titles.push(title.clone());
+
+                Project { title, ..project }
+            })
+            .collect()
+    }
+
+    // Add the imported projects to the board: a project with the same title of an existing one
+    // is merged into it, and its tasks with an already present title are skipped.
+    // Return the new board with a summary of the changes
+    pub fn merge(projects: &[Project], imported: Vec<Project>) -> (Vec<Project>, Vec<String>) {
+        let mut new_projects = projects.to_vec();
+        let mut summary: Vec<String> = vec![];
+
+        for imported_project in imported {
+            let index = match new_projects
+                .iter()
+                .position(|p| p.title == imported_project.title)
+            {
+                Some(index) => {
+                    summary.push(format!("Project \"{}\" (merged)", imported_project.title));
+                    index
+                }
+                None => {
+                    summary.push(format!("Project \"{}\" (new)", imported_project.title));
+                    new_projects.push(Project {
+                        tasks: vec![],
+                        ..imported_project.clone()
+                    });
+                    new_projects.len() - 1
+                }
+            };
+
+            for mut task in imported_project.tasks {
+                let tasks = &mut new_projects[index].tasks;
+
+                if tasks.iter().any(|t| t.title == task.title) {
+                    summary.push(format!("  = {} (already present)", task.title));
+                    continue;
+                }
+
+                summary.push(format!("  + [{}] {}", task.status, task.title));
+                Task::fill_timestamps(&mut task);
+                tasks.push(task);
+            }
+        }
+
+        (new_projects, summary)
+    }
+}
@@ -0,0 +1,351 @@
+use std::{collections::HashMap, error::Error};
+
+use csv::{ReaderBuilder, Writer};
+use serde_json::{from_value, to_value, Map, Value};
+
+use crate::{
+    interop::Interop,
+    project::Project,
+    task::{Task, TASK_PRIORITIES, TASK_STATUSES, TASK_STATUS_UP_NEXT},
+};
+
+pub struct Csv;
+
+// First columns, the other task fields follow in alphabetical order
+static FIRST_COLUMNS: [&str; 4] = ["project", "title", "status", "priority"];
+
+impl Csv {
+    // Task fields as they are serialized in the json file
+    fn get_task_fields(task: &Task) -> Map<String, Value> {
+        match to_value(task).unwrap() {
+            Value::Object(fields) => fields,
+            _ => Map::new(),
+        }
+    }
+
+    fn get_columns() -> Vec<String> {
+        let mut other_columns: Vec<String> = Csv::get_task_fields(&Csv::get_default_task())
+            .into_iter()
+            .map(|(key, _)| key)
+            .filter(|key| !FIRST_COLUMNS.contains(&key.as_str()))
+            .collect();
+        other_columns.sort();
+
+        FIRST_COLUMNS
+            .into_iter()
+            .map(|c| c.to_string())
+            .chain(other_columns)
+            .collect()
+    }
+
+    fn get_default_task() -> Task {
+        Task {
+            id: None,
+            title: "".to_string(),
+            status: TASK_STATUS_UP_NEXT.to_string(),
+            priority: 0,
+            ..Default::default()
+        }
+    }
+
+    // Fields of the text types (`String` and `Option<String>`), the ones accepting a string.
+    // A date is a string in json but not any string, so it's not a text field
+    fn get_text_fields() -> Vec<String> {
+        let default_fields = Csv::get_task_fields(&Csv::get_default_task());
+
+        default_fields
+            .keys()
+            .filter(|field| {
+                let mut fields = default_fields.clone();
+                fields.insert(field.to_string(), Value::from(""));
+
+                from_value::<Task>(Value::Object(fields)).is_ok()
+            })
+            .cloned()
+            .collect()
+    }
+
+    // One row per task, the fields which are not text are written as json
+    pub fn export(projects: &[Project]) -> Result<String, Box<dyn Error>> {
+        let columns = Csv::get_columns();
+        let mut writer = Writer::from_writer(vec![]);
+
+        writer.write_record(&columns)?;
+
+        for project in projects {
+            let mut tasks = project.tasks.clone();
+            Task::sort(&mut tasks);
+
+            for task in tasks.iter() {
+                let mut fields = Csv::get_task_fields(task);
+                fields.insert("project".to_string(), Value::from(project.title.clone()));
+
+                let record = columns.iter().map(|column| match fields.get(column) {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(Value::Null) | None => "".to_string(),
+                    Some(value) => value.to_string(),
+                });
+
+                writer.write_record(record)?;
+            }
+        }
+
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+
+    // Mapping between the task fields and the columns of the file,
+    // i.e. "title=Name,status=State" (the fields not mapped use their own name)
+    pub fn parse_mapping(value: &str) -> Result<HashMap<String, String>, Box<dyn Error>> {
+        let columns = Csv::get_columns();
+        let mut mapping: HashMap<String, String> = HashMap::new();
+
+        for pair in value.split(',').filter(|p| !p.trim().is_empty()) {
+            let Some((field, column)) = pair.split_once('=') else {
+                return Err(
+                    format!("Invalid mapping \"{pair}\", expected <field>=<column>").into(),
+                );
+            };
+
+            if !columns.contains(&field.trim().to_string()) {
+                return Err(format!(
+                    "Unknown field \"{}\", expected one of: {}",
+                    field.trim(),
+                    columns.join(", ")
+                )
+                .into());
+            }
+
+            mapping.insert(field.trim().to_string(), column.trim().to_string());
+        }
+
+        Ok(mapping)
+    }
+
+    // Rows are grouped by the project column (or into the default project),
+    // every invalid row is reported and nothing is imported
+    pub fn import(
+        content: &str,
+        default_title: &str,
+        mapping: &HashMap<String, String>,
+    ) -> Result<Vec<Project>, Box<dyn Error>> {
+        let mut reader = ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+
+        let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_string()).collect();
+
+        // Position of the column of each field
+        let positions: HashMap<String, usize> = Csv::get_columns()
+            .into_iter()
+            .filter_map(|field| {
+                let column = mapping.get(&field).unwrap_or(&field);
+                let position = headers.iter().position(|h| h == column)?;
+
+                Some((field, position))
+            })
+            .collect();
+
+        if !positions.contains_key("title") {
+            let column = mapping.get("title").map_or("title", |c| c.as_str());
+            return Err(format!("Missing the \"{column}\" column for the task title").into());
+        }
+
+        let default_fields = Csv::get_task_fields(&Csv::get_default_task());
+        let text_fields = Csv::get_text_fields();
+        let mut projects: Vec<Project> = vec![];
+        let mut errors: Vec<String> = vec![];
+
+        for (index, record) in reader.records().enumerate() {
+            // Header is the first line
+            let row = index + 2;
+            let record = record?;
+
+            let get_cell = |field: &str| {
+                positions
+                    .get(field)
+                    .and_then(|p| record.get(*p))
+                    .unwrap_or("")
+            };
+
+            let mut fields = default_fields.clone();
+
+            for field in default_fields.keys() {
+                let cell = get_cell(field);
+
+                if cell.is_empty() {
+                    continue;
+                }
+
+                // Text fields are kept as they are (i.e. an uid of digits),
+                // the others are parsed as json
+                let value = if text_fields.contains(field) {
+                    Value::from(cell)
+                } else {
+                    serde_json::from_str(cell).unwrap_or(Value::from(cell))
+                };
+
+                fields.insert(field.clone(), value);
+            }
+
+            let task = match from_value::<Task>(Value::Object(fields)) {
+                Ok(task) => task,
+                Err(e) => {
+                    errors.push(format!("Row {row}: {e}"));
+                    continue;
+                }
+            };
+
+            if task.title.is_empty() {
+                errors.push(format!("Row {row}: empty title"));
+                continue;
+            }
+
+            if !TASK_STATUSES.contains(&task.status.as_str()) {
+                errors.push(format!(
+                    "Row {row}: unknown status \"{}\", expected one of: {}",
+                    task.status,
+                    TASK_STATUSES.join(", ")
+                ));
+                continue;
+            }
+
+            if !TASK_PRIORITIES.contains(&task.priority) {
+                errors.push(format!("Row {row}: unknown priority \"{}\"", task.priority));
+                continue;
+            }
+
+            let project_title = match get_cell("project") {
+                "" => default_title,
+                title => title,
+            };
+
+            Interop::add_task(&mut projects, project_title, task);
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.join("\n").into());
+        }
+
+        Ok(projects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        recurrence::Recurrence,
+        task::{ChecklistItem, TASK_STATUS_DONE},
+        util::test_helpers::date,
+    };
+
+    fn get_projects() -> Vec<Project> {
+        let created_at = Interop::parse_date("20261001T080000Z");
+
+        vec![
+            Project {
+                id: None,
+                title: "Home, sweet home".to_string(),
+                tasks: vec![Task {
+                    // Generated uids can be all digits
+                    uid: Some("1234567890123456".to_string()),
+                    title: "Paint \"the\" wall".to_string(),
+                    status: TASK_STATUS_UP_NEXT.to_string(),
+                    priority: 1,
+                    description: "first line\nsecond line".to_string(),
+                    tags: vec!["house".to_string()],
+                    checklist: vec![ChecklistItem {
+                        title: "buy paint".to_string(),
+                        done: true,
+                    }],
+                    created_at,
+                    updated_at: created_at,
+                    due_date: Some(date("2026-10-20")),
+                    recurrence: Some(Recurrence::Month { day: 20 }),
+                    blocked_by: vec!["0000000000000042".to_string()],
+                    ..Default::default()
+                }],
+            },
+            Project {
+                id: None,
+                title: "Work".to_string(),
+                tasks: vec![Task {
+                    uid: Some("0000000000000042".to_string()),
+                    title: "123".to_string(),
+                    status: TASK_STATUS_DONE.to_string(),
+                    priority: 0,
+                    created_at,
+                    updated_at: created_at,
+                    completed_at: created_at,
+                    ..Default::default()
+                }],
+            },
+        ]
+    }
+
+    #[test]
+    fn text_fields() {
+        let mut text_fields = Csv::get_text_fields();
+        text_fields.sort();
+
+        assert_eq!(text_fields, vec!["description", "status", "title", "uid"]);
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let projects = get_projects();
+        let exported = Csv::export(&projects).unwrap();
+        let imported = Csv::import(&exported, "Default", &HashMap::new()).unwrap();
+
+        assert_eq!(
+            to_value(&imported).unwrap(),
+            to_value(&projects).unwrap(),
+            "{exported}"
+        );
+    }
+
+    #[test]
+    fn import_with_mapping() {
+        let content = "Name,State,List,Labels\nPaint,OnGoing,Home,\"[\"\"house\"\"]\"\nClean,,,\n";
+        let mapping =
+            Csv::parse_mapping("title=Name, status=State,project=List,tags=Labels").unwrap();
+        let projects = Csv::import(content, "Default", &mapping).unwrap();
+
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].title, "Home");
+        assert_eq!(projects[0].tasks[0].status, "OnGoing");
+        assert_eq!(projects[0].tasks[0].tags, vec!["house"]);
+        assert_eq!(projects[1].title, "Default");
+        assert_eq!(projects[1].tasks[0].status, TASK_STATUS_UP_NEXT);
+    }
+
+    #[test]
+    fn invalid_mapping() {
+        assert!(Csv::parse_mapping("title").is_err());
+        assert!(Csv::parse_mapping("name=Title").is_err());
+        assert!(Csv::parse_mapping("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn import_reports_the_invalid_rows() {
+        let content =
+            "title,status,priority\n,UpNext,0\nA,Later,0\nB,UpNext,7\nC,UpNext,high\nD,Done,0\n";
+        let error = Csv::import(content, "Default", &HashMap::new())
+            .unwrap_err()
+            .to_string();
+        let lines: Vec<&str> = error.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "Row 2: empty title");
+        assert!(lines[1].starts_with("Row 3: unknown status \"Later\""));
+        assert_eq!(lines[2], "Row 4: unknown priority \"7\"");
+        assert!(lines[3].starts_with("Row 5: "));
+
+        let error = Csv::import("name\nA\n", "Default", &HashMap::new()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Missing the \"title\" column for the task title"
+        );
+    }
+}
@@ -0,0 +1,446 @@
+use std::error::Error;
+
+use chrono::NaiveDate;
+
+use crate::{
+    interop::Interop,
+    project::Project,
+    task::{Task, TASK_STATUS_DONE, TASK_STATUS_ON_GOING, TASK_STATUS_UP_NEXT},
+    util::Util,
+};
+
+// iCalendar VTODO components, cf. https://datatracker.ietf.org/doc/html/rfc5545#section-3.6.2
+pub struct Ics;
+
+static STATUSES: [(&str, &str); 3] = [
+    (TASK_STATUS_UP_NEXT, "NEEDS-ACTION"),
+    (TASK_STATUS_ON_GOING, "IN-PROCESS"),
+    (TASK_STATUS_DONE, "COMPLETED"),
+];
+
+// iCalendar priorities go from 1 (highest) to 9 (lowest), 0 is undefined
+static PRIORITIES: [(u8, u8); 3] = [(1, 1), (2, 5), (3, 9)];
+
+// Due dates are days, without time
+static DATE_FORMAT: &str = "%Y%m%d";
+
+// Max length of a line before folding it
+static LINE_LENGTH: usize = 75;
+
+impl Ics {
+    pub fn export(projects: &[Project]) -> String {
+        let timestamp = Interop::format_date(Util::get_now());
+
+        let mut lines: Vec<String> = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!(
+                "PRODID:-//{}//{} {}//EN",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION")
+            ),
+            format!("X-WR-CALNAME:{}", env!("CARGO_PKG_NAME")),
+        ];
+
+        for project in projects {
+            let mut tasks = project.tasks.clone();
+            Task::sort(&mut tasks);
+
+            for task in tasks.iter() {
+                lines.push("BEGIN:VTODO".to_string());
+                lines.push(format!("UID:{}", Ics::get_uid(task)));
+                lines.push(format!("DTSTAMP:{timestamp}"));
+                lines.push(format!("SUMMARY:{}", Ics::escape(&task.title)));
+
+                for (name, date) in [
+                    ("CREATED", task.created_at),
+                    ("LAST-MODIFIED", task.updated_at),
+                    ("COMPLETED", task.completed_at),
+                ] {
+                    if let Some(date) = date {
+                        lines.push(format!("{name}:{}", Interop::format_date(date)));
+                    }
+                }
+
+                if let Some(due_date) = task.due_date {
+                    lines.push(format!("DUE;VALUE=DATE:{}", due_date.format(DATE_FORMAT)));
+                }
+
+                lines.push(format!("CATEGORIES:{}", Ics::escape(&project.title)));
+
+                if let Some((_, status)) = STATUSES.iter().find(|(s, _)| *s == task.status) {
+                    lines.push(format!("STATUS:{status}"));
+                }
+
+                if let Some((_, priority)) = PRIORITIES.iter().find(|(p, _)| *p == task.priority) {
+                    lines.push(format!("PRIORITY:{priority}"));
+                }
+
+                lines.push("END:VTODO".to_string());
+            }
+        }
+
+        lines.push("END:VCALENDAR".to_string());
+
+        lines.iter().map(|l| Ics::fold(l) + "\r\n").collect()
+    }
+
+    // The uids are created before the export, see `Interop::create_uids`.
+    // The ones imported from other tools are already complete ("id@domain")
+    fn get_uid(task: &Task) -> String {
+        let uid = task.uid.as_deref().unwrap_or_default();
+
+        if uid.contains('@') {
+            return uid.to_string();
+        }
+
+        format!("{uid}@{}", env!("CARGO_PKG_NAME"))
+    }
+
+    // Reverse of `get_uid`
+    fn get_task_uid(uid: &str) -> Option<String> {
+        let uid = Ics::unescape(uid).trim().to_string();
+        let uid = match uid.strip_suffix(concat!("@", env!("CARGO_PKG_NAME"))) {
+            Some(task_uid) => task_uid.to_string(),
+            None => uid,
+        };
+
+        Some(uid).filter(|u| !u.is_empty())
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('\\', "\\\\")
+            .replace(';', "\\;")
+            .replace(',', "\\,")
+            .replace('\n', "\\n")
+    }
+
+    fn unescape(text: &str) -> String {
+        let mut result = String::new();
+        let mut chars = text.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                result.push(c);
+                continue;
+            }
+
+            match chars.next() {
+                Some('n') | Some('N') => result.push('\n'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+        }
+
+        result
+    }
+
+    // Long lines are split, the next ones start with a space
+    fn fold(line: &str) -> String {
+        let mut folded = String::new();
+        let mut length = 0;
+
+        for c in line.chars() {
+            if length + c.len_utf8() > LINE_LENGTH {
+                folded.push_str("\r\n ");
+                length = 1;
+            }
+
+            folded.push(c);
+            length += c.len_utf8();
+        }
+
+        folded
+    }
+
+    fn unfold(content: &str) -> Vec<String> {
+        let mut lines: Vec<String> = vec![];
+
+        for line in content.lines() {
+            match line.strip_prefix(' ').or(line.strip_prefix('\t')) {
+                Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+                _ => lines.push(line.to_string()),
+            }
+        }
+
+        lines
+    }
+
+    // The tasks go into the project of their first category,
+    // or into the calendar name or the default title
+    pub fn import(content: &str, default_title: &str) -> Result<Vec<Project>, Box<dyn Error>> {
+        let mut projects: Vec<Project> = vec![];
+        let mut errors: Vec<String> = vec![];
+
+        let mut calendar_title = default_title.to_string();
+        // Properties of the current VTODO
+        let mut todo: Option<Vec<(String, String)>> = None;
+
+        for (index, line) in Ics::unfold(content).iter().enumerate() {
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+
+            // Parameters (i.e. "SUMMARY;LANGUAGE=en") are ignored
+            let name = name.split(';').next().unwrap().to_uppercase();
+
+            match (name.as_str(), value) {
+                ("BEGIN", "VTODO") => todo = Some(vec![]),
+                ("END", "VTODO") => {
+                    let Some(properties) = todo.take() else {
+                        continue;
+                    };
+
+                    let get = |name: &str| {
+                        properties
+                            .iter()
+                            .find(|(n, _)| n == name)
+                            .map(|(_, v)| v.as_str())
+                    };
+
+                    match Ics::get_task(get("SUMMARY"), get("STATUS"), get("PRIORITY")) {
+                        Ok(mut task) => {
+                            task.uid = get("UID").and_then(Ics::get_task_uid);
+                            task.created_at = get("CREATED").and_then(Interop::parse_date);
+                            task.updated_at = get("LAST-MODIFIED").and_then(Interop::parse_date);
+                            // A due date with time ("20240131T093000Z") keeps only the day
+                            task.due_date = get("DUE").and_then(|d| {
+                                NaiveDate::parse_from_str(d.get(..8)?, DATE_FORMAT).ok()
+                            });
+
+                            if task.status == TASK_STATUS_DONE {
+                                task.completed_at = get("COMPLETED").and_then(Interop::parse_date);
+                            }
+
+                            let project_title = get("CATEGORIES")
+                                .and_then(|c| Ics::split_list(c).into_iter().next())
+                                .unwrap_or(calendar_title.clone());
+
+                            Interop::add_task(&mut projects, &project_title, task);
+                        }
+                        Err(e) => errors.push(format!("Line {}: {e}", index + 1)),
+                    }
+                }
+                ("X-WR-CALNAME", value) if todo.is_none() => calendar_title = Ics::unescape(value),
+                (name, value) => {
+                    if let Some(properties) = todo.as_mut() {
+                        properties.push((name.to_string(), value.to_string()));
+                    }
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.join("\n").into());
+        }
+
+        Ok(projects)
+    }
+
+    // "a\,b,c" => ["a,b", "c"]
+    fn split_list(value: &str) -> Vec<String> {
+        let mut items: Vec<String> = vec![String::new()];
+        let mut is_escaped = false;
+
+        for c in value.chars() {
+            if c == ',' && !is_escaped {
+                items.push(String::new());
+                continue;
+            }
+
+            is_escaped = c == '\\' && !is_escaped;
+            items.last_mut().unwrap().push(c);
+        }
+
+        items
+            .iter()
+            .map(|i| Ics::unescape(i).trim().to_string())
+            .filter(|i| !i.is_empty())
+            .collect()
+    }
+
+    fn get_task(
+        summary: Option<&str>,
+        status: Option<&str>,
+        priority: Option<&str>,
+    ) -> Result<Task, String> {
+        let title = Ics::unescape(summary.unwrap_or("")).trim().to_string();
+
+        if title.is_empty() {
+            return Err("VTODO without SUMMARY".to_string());
+        }
+
+        let status = match status.map(|s| s.trim().to_uppercase()).as_deref() {
+            None => TASK_STATUS_UP_NEXT,
+            // There is no cancelled status, the task is closed anyway
+            Some("CANCELLED") => TASK_STATUS_DONE,
+            Some(value) => match STATUSES.iter().find(|(_, s)| *s == value) {
+                Some((status, _)) => status,
+                None => return Err(format!("unknown STATUS \"{value}\"")),
+            },
+        };
+
+        // 1-4 high, 5 medium, 6-9 low
+        let priority = match priority.map(|p| p.trim().parse::<u8>()) {
+            None | Some(Ok(0)) => 0,
+            Some(Ok(1..=4)) => 1,
+            Some(Ok(5)) => 2,
+            Some(Ok(6..=9)) => 3,
+            Some(_) => return Err(format!("invalid PRIORITY \"{}\"", priority.unwrap())),
+        };
+
+        Ok(Task {
+            id: None,
+            title,
+            status: status.to_string(),
+            priority: if status == TASK_STATUS_DONE {
+                0
+            } else {
+                priority
+            },
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_helpers::date;
+
+    fn get_projects() -> Vec<Project> {
+        let created_at = Interop::parse_date("20261001T080000Z");
+        let task = |uid: &str, title: &str, status: &str, priority: u8| Task {
+            uid: Some(uid.to_string()),
+            title: title.to_string(),
+            status: status.to_string(),
+            priority,
+            created_at,
+            updated_at: created_at,
+            ..Default::default()
+        };
+
+        vec![
+            Project {
+                id: None,
+                title: "Home; sweet, home".to_string(),
+                tasks: vec![
+                    Task {
+                        due_date: Some(date("2026-10-20")),
+                        ..task("00000000000000aa", "Paint the wall", TASK_STATUS_UP_NEXT, 1)
+                    },
+                    task("event-1@calendar.example", "Clean", TASK_STATUS_ON_GOING, 2),
+                ],
+            },
+            Project {
+                id: None,
+                title: "Work".to_string(),
+                tasks: vec![Task {
+                    completed_at: created_at,
+                    ..task("00000000000000bb", "Ship", TASK_STATUS_DONE, 0)
+                }],
+            },
+        ]
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let exported = Ics::export(&get_projects());
+        let imported = Ics::import(&exported, "Default").unwrap();
+
+        let titles: Vec<&str> = imported.iter().map(|p| p.title.as_str()).collect();
+        assert_eq!(titles, vec!["Home; sweet, home", "Work"]);
+
+        let paint = &imported[0].tasks[0];
+        assert_eq!(paint.uid.as_deref(), Some("00000000000000aa"));
+        assert_eq!(paint.title, "Paint the wall");
+        assert_eq!(paint.priority, 1);
+        assert_eq!(paint.due_date, Some(date("2026-10-20")));
+        assert_eq!(paint.created_at, Interop::parse_date("20261001T080000Z"));
+
+        let clean = &imported[0].tasks[1];
+        assert_eq!(clean.uid.as_deref(), Some("event-1@calendar.example"));
+        assert_eq!(clean.status, TASK_STATUS_ON_GOING);
+        assert_eq!(clean.priority, 2);
+
+        let ship = &imported[1].tasks[0];
+        assert_eq!(ship.status, TASK_STATUS_DONE);
+        assert!(ship.completed_at.is_some());
+
+        // Same UIDs on the next export
+        let uids = |content: &str| -> Vec<String> {
+            Ics::unfold(content)
+                .into_iter()
+                .filter(|l| l.starts_with("UID:"))
+                .collect()
+        };
+        assert_eq!(uids(&Ics::export(&imported)), uids(&exported));
+    }
+
+    #[test]
+    fn fold_and_unfold() {
+        let line = format!("SUMMARY:{}", "é".repeat(60));
+        let folded = Ics::fold(&line);
+
+        assert!(folded.split("\r\n").all(|l| l.len() <= LINE_LENGTH));
+        assert_eq!(Ics::unfold(&folded), vec![line.clone()]);
+        assert_eq!(Ics::fold("SUMMARY:short"), "SUMMARY:short");
+        assert_eq!(
+            Ics::unfold("A:1\r\n  2\r\n\t3\r\nB:4"),
+            vec!["A:1 23", "B:4"]
+        );
+    }
+
+    #[test]
+    fn escape_and_unescape() {
+        let text = "a,b;c\\d\ne";
+
+        assert_eq!(Ics::escape(text), r"a\,b\;c\\d\ne");
+        assert_eq!(Ics::unescape(&Ics::escape(text)), text);
+        assert_eq!(Ics::unescape("line\\Nbreak\\"), "line\nbreak\\");
+    }
+
+    #[test]
+    fn split_list() {
+        assert_eq!(Ics::split_list("a\\,b, c,,d\\\\"), vec!["a,b", "c", "d\\"]);
+        assert!(Ics::split_list(" , ").is_empty());
+    }
+
+    #[test]
+    fn get_task() {
+        let task = Ics::get_task(Some(" Call\\, mum "), Some("cancelled"), Some("3")).unwrap();
+        assert_eq!(task.title, "Call, mum");
+        assert_eq!(task.status, TASK_STATUS_DONE);
+        // Done tasks have no priority
+        assert_eq!(task.priority, 0);
+
+        for (priority, expected) in [
+            (None, 0),
+            (Some("0"), 0),
+            (Some("4"), 1),
+            (Some("5"), 2),
+            (Some("9"), 3),
+        ] {
+            let task = Ics::get_task(Some("T"), None, priority).unwrap();
+            assert_eq!(task.status, TASK_STATUS_UP_NEXT);
+            assert_eq!(task.priority, expected, "{priority:?}");
+        }
+
+        assert!(Ics::get_task(None, None, None).is_err());
+        assert!(Ics::get_task(Some("T"), Some("DELEGATED"), None).is_err());
+        assert!(Ics::get_task(Some("T"), None, Some("10")).is_err());
+    }
+
+    #[test]
+    fn import_reports_the_invalid_todos() {
+        let content = "BEGIN:VCALENDAR\r\nX-WR-CALNAME:Chores\r\nBEGIN:VTODO\r\nSUMMARY:A\r\nEND:VTODO\r\nBEGIN:VTODO\r\nSTATUS:COMPLETED\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+        let error = Ics::import(content, "Default").unwrap_err().to_string();
+        assert_eq!(error, "Line 8: VTODO without SUMMARY");
+
+        let content = "BEGIN:VCALENDAR\r\nX-WR-CALNAME:Chores\r\nBEGIN:VTODO\r\nSUMMARY:A\r\nDUE:20261020T093000Z\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+        let projects = Ics::import(content, "Default").unwrap();
+        assert_eq!(projects[0].title, "Chores");
+        assert_eq!(projects[0].tasks[0].due_date, Some(date("2026-10-20")));
+    }
+}
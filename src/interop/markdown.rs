@@ -0,0 +1,277 @@
+use crate::{
+    project::Project,
+    task::{Task, TASK_STATUSES, TASK_STATUS_DONE, TASK_STATUS_UP_NEXT},
+    util::Util,
+};
+
+pub struct Markdown;
+
+impl Markdown {
+    // Each project is a heading and each task a checklist item annotated like in the app
+    // i.e. "- [ ] [!!] [OnGoing] Task title"
+    pub fn export(projects: &[Project]) -> String {
+        projects
+            .iter()
+            .map(|project| {
+                let mut tasks = project.tasks.clone();
+                Task::sort(&mut tasks);
+
+                let mut lines = vec![format!("# {}", project.title)];
+
+                if !tasks.is_empty() {
+                    lines.push("".to_string());
+                    lines.extend(tasks.iter().map(Markdown::get_task_line));
+                }
+
+                lines.join("\n") + "\n"
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn get_task_line(task: &Task) -> String {
+        let checkbox = if task.status == TASK_STATUS_DONE {
+            "[x]"
+        } else {
+            "[ ]"
+        };
+
+        let priority = if task.priority != 0 {
+            format!("[{}] ", Util::get_priority_indicator(task.priority))
+        } else {
+            "".to_string()
+        };
+
+        format!("- {checkbox} {priority}[{}] {}", task.status, task.title)
+    }
+
+    // Headings become projects and checklist items (at any depth) become tasks.
+    // Items before the first heading are added to a project with the default title
+    pub fn import(content: &str, default_title: &str) -> Vec<Project> {
+        let mut projects: Vec<Project> = vec![];
+
+        for line in content.lines() {
+            let line = line.trim();
+
+            if let Some(title) = Markdown::parse_heading(line) {
+                projects.push(Markdown::get_empty_project(title));
+                continue;
+            }
+
+            let Some(task) = Markdown::parse_task(line) else {
+                continue;
+            };
+
+            if projects.is_empty() {
+                projects.push(Markdown::get_empty_project(default_title));
+            }
+
+            projects.last_mut().unwrap().tasks.push(task);
+        }
+
+        projects
+    }
+
+    fn get_empty_project(title: &str) -> Project {
+        Project {
+            id: None,
+            title: title.to_string(),
+            tasks: vec![],
+        }
+    }
+
+    // "## Title" => "Title"
+    fn parse_heading(line: &str) -> Option<&str> {
+        let title = line.trim_start_matches('#');
+        let level = line.len() - title.len();
+
+        if level == 0 || level > 6 || !title.starts_with(' ') {
+            return None;
+        }
+
+        Some(title.trim()).filter(|t| !t.is_empty())
+    }
+
+    // "- [x] [!!] [Done] Title" => Task, the annotations of the export are optional
+    fn parse_task(line: &str) -> Option<Task> {
+        let item = line
+            .strip_prefix("- ")
+            .or(line.strip_prefix("* "))
+            .or(line.strip_prefix("+ "))?;
+
+        let (is_checked, rest) = match item.get(..4) {
+            Some("[ ] ") => (false, &item[4..]),
+            Some("[x] ") | Some("[X] ") => (true, &item[4..]),
+            _ => return None,
+        };
+
+        let mut title = rest.trim();
+        let mut priority = 0;
+        let mut status = TASK_STATUS_UP_NEXT.to_string();
+
+        // Priority indicator, i.e. "[!!]"
+        if let Some((annotation, rest)) = Markdown::split_annotation(title) {
+            if let Some(value) =
+                Util::get_priority_from_indicator(annotation).filter(|_| !annotation.is_empty())
+            {
+                priority = value;
+                title = rest;
+            }
+        }
+
+        // Status, i.e. "[OnGoing]"
+        if let Some((annotation, rest)) = Markdown::split_annotation(title) {
+            if TASK_STATUSES.contains(&annotation) {
+                status = annotation.to_string();
+                title = rest;
+            }
+        }
+
+        // The checkbox wins over the status annotation
+        if is_checked {
+            status = TASK_STATUS_DONE.to_string();
+        } else if status == TASK_STATUS_DONE {
+            status = TASK_STATUS_UP_NEXT.to_string();
+        }
+
+        if title.is_empty() {
+            return None;
+        }
+
+        Some(Task {
+            id: None,
+            title: title.to_string(),
+            priority: if status == TASK_STATUS_DONE {
+                0
+            } else {
+                priority
+            },
+            status,
+            ..Default::default()
+        })
+    }
+
+    // "[annotation] rest" => ("annotation", "rest")
+    fn split_annotation(text: &str) -> Option<(&str, &str)> {
+        let (annotation, rest) = text.strip_prefix('[')?.split_once(']')?;
+
+        Some((annotation, rest.trim_start()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TASK_STATUS_ON_GOING;
+
+    fn get_projects() -> Vec<Project> {
+        vec![
+            Project {
+                id: None,
+                title: "Home".to_string(),
+                tasks: vec![
+                    Task {
+                        title: "Paint [the] wall".to_string(),
+                        status: TASK_STATUS_ON_GOING.to_string(),
+                        priority: 1,
+                        ..Default::default()
+                    },
+                    Task {
+                        title: "Clean".to_string(),
+                        status: TASK_STATUS_DONE.to_string(),
+                        ..Default::default()
+                    },
+                ],
+            },
+            Markdown::get_empty_project("Empty"),
+        ]
+    }
+
+    // Title, status and priority of a task
+    type TaskFields = (String, String, u8);
+
+    fn get_fields(projects: &[Project]) -> Vec<(String, Vec<TaskFields>)> {
+        projects
+            .iter()
+            .map(|p| {
+                (
+                    p.title.clone(),
+                    p.tasks
+                        .iter()
+                        .map(|t| (t.title.clone(), t.status.clone(), t.priority))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let exported = Markdown::export(&get_projects());
+
+        assert_eq!(
+            exported,
+            "# Home\n\n- [ ] [!!!] [OnGoing] Paint [the] wall\n- [x] [Done] Clean\n\n# Empty\n"
+        );
+        assert_eq!(
+            get_fields(&Markdown::import(&exported, "Default")),
+            get_fields(&get_projects())
+        );
+    }
+
+    #[test]
+    fn import_items_and_headings() {
+        let content = "- [ ] Before the heading\n\
+                       ### Work\n\
+                       \x20 * [X] [OnGoing] Nested and checked\n\
+                       + [ ] [Done] [!] Unchecked wins\n\
+                       - [ ] [!] Low\n";
+
+        assert_eq!(
+            get_fields(&Markdown::import(content, "Default")),
+            [
+                (
+                    "Default".to_string(),
+                    vec![(
+                        "Before the heading".to_string(),
+                        TASK_STATUS_UP_NEXT.to_string(),
+                        0
+                    )]
+                ),
+                (
+                    "Work".to_string(),
+                    vec![
+                        (
+                            "Nested and checked".to_string(),
+                            TASK_STATUS_DONE.to_string(),
+                            0
+                        ),
+                        (
+                            "[!] Unchecked wins".to_string(),
+                            TASK_STATUS_UP_NEXT.to_string(),
+                            0
+                        ),
+                        ("Low".to_string(), TASK_STATUS_UP_NEXT.to_string(), 3),
+                    ]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn import_skips_the_invalid_lines() {
+        let content = "Some text\n\
+                       #Not a heading\n\
+                       ####### Too deep\n\
+                       #\n\
+                       - plain item\n\
+                       - [] no checkbox\n\
+                       - [ ]\n\
+                       - [ ] [Done]\n";
+
+        assert!(Markdown::import(content, "Default").is_empty());
+        assert_eq!(Markdown::parse_heading("## Title "), Some("Title"));
+        assert_eq!(Markdown::split_annotation("[a] b"), Some(("a", "b")));
+        assert_eq!(Markdown::split_annotation("[a b"), None);
+    }
+}
@@ -0,0 +1,419 @@
+use std::error::Error;
+
+use chrono::{Local, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_string_pretty};
+
+use crate::{
+    interop::Interop,
+    project::Project,
+    task::{Task, TASK_STATUS_DONE, TASK_STATUS_ON_GOING, TASK_STATUS_UP_NEXT},
+    util::Util,
+};
+
+// Output of `task export`, cf. https://taskwarrior.org/docs/design/task/
+pub struct Taskwarrior;
+
+// Only the attributes used by basilk, the others are ignored
+#[derive(Deserialize, Serialize)]
+struct TaskwarriorTask {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    uuid: Option<String>,
+    description: String,
+    status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    priority: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    annotations: Vec<Annotation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    entry: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    modified: Option<String>,
+    // Taskwarrior has no "OnGoing" status, a started task is an active one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    end: Option<String>,
+    // The local midnight of the due day
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    due: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Annotation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    entry: Option<String>,
+    description: String,
+}
+
+static STATUS_PENDING: &str = "pending";
+static STATUS_COMPLETED: &str = "completed";
+
+// Priorities of Taskwarrior in the same order of `TASK_PRIORITIES`
+static PRIORITY_LETTERS: [(&str, u8); 3] = [("H", 1), ("M", 2), ("L", 3)];
+
+impl Taskwarrior {
+    pub fn export(projects: &[Project]) -> String {
+        let now = Util::get_now();
+        let mut exported: Vec<TaskwarriorTask> = vec![];
+
+        for project in projects {
+            let mut tasks = project.tasks.clone();
+            Task::sort(&mut tasks);
+
+            for task in tasks.iter() {
+                let is_done = task.status == TASK_STATUS_DONE;
+                let entry = Interop::format_date(task.created_at.unwrap_or(now));
+                let modified = Interop::format_date(task.updated_at.unwrap_or(now));
+
+                exported.push(TaskwarriorTask {
+                    uuid: Some(Taskwarrior::get_uuid(task)),
+                    description: task.title.clone(),
+                    status: if is_done {
+                        STATUS_COMPLETED.to_string()
+                    } else {
+                        STATUS_PENDING.to_string()
+                    },
+                    project: Some(project.title.clone()),
+                    priority: PRIORITY_LETTERS
+                        .iter()
+                        .find(|(_, p)| *p == task.priority)
+                        .map(|(letter, _)| letter.to_string()),
+                    tags: task.tags.clone(),
+                    annotations: task
+                        .description
+                        .lines()
+                        .filter(|l| !l.trim().is_empty())
+                        .map(|l| Annotation {
+                            entry: Some(modified.clone()),
+                            description: l.to_string(),
+                        })
+                        .collect(),
+                    entry: Some(entry),
+                    start: (task.status == TASK_STATUS_ON_GOING).then(|| modified.clone()),
+                    end: is_done.then(|| Interop::format_date(task.completed_at.unwrap_or(now))),
+                    due: task.due_date.map(Taskwarrior::format_due),
+                    modified: Some(modified),
+                });
+            }
+        }
+
+        format!("{}\n", to_string_pretty(&exported).unwrap())
+    }
+
+    // The uuid of the tasks imported from Taskwarrior, or one made from the hashes of the uid,
+    // so `task import` updates the tasks already imported instead of duplicating them.
+    // The uids are created before the export, see `Interop::create_uids`
+    fn get_uuid(task: &Task) -> String {
+        let uid = task.uid.as_deref().unwrap_or_default();
+
+        if Taskwarrior::is_uuid(uid) {
+            return uid.to_string();
+        }
+
+        let high = Interop::get_hash(uid);
+        let low = Interop::get_hash(&format!("{uid}\n{uid}"));
+        let hex = format!("{high:016x}{low:016x}");
+
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    }
+
+    // "xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx" with hex digits
+    fn is_uuid(value: &str) -> bool {
+        let groups: Vec<&str> = value.split('-').collect();
+
+        groups.iter().map(|g| g.len()).eq([8, 4, 4, 4, 12])
+            && groups
+                .iter()
+                .all(|g| g.chars().all(|c| c.is_ascii_hexdigit()))
+    }
+
+    fn format_due(date: NaiveDate) -> String {
+        let midnight = date.and_time(NaiveTime::MIN);
+
+        // Midnight can be skipped by a daylight saving change
+        let due = match midnight.and_local_timezone(Local).earliest() {
+            Some(due) => due.with_timezone(&Utc),
+            None => midnight.and_utc(),
+        };
+
+        Interop::format_date(due)
+    }
+
+    // Both the json array of `task export` and the older format with one task per line
+    fn parse(content: &str) -> Result<Vec<TaskwarriorTask>, Box<dyn Error>> {
+        if content.trim_start().starts_with('[') {
+            return Ok(from_str::<Vec<TaskwarriorTask>>(content)?);
+        }
+
+        let mut tasks: Vec<TaskwarriorTask> = vec![];
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim().trim_end_matches(',');
+
+            if line.is_empty() {
+                continue;
+            }
+
+            match from_str::<TaskwarriorTask>(line) {
+                Ok(task) => tasks.push(task),
+                Err(e) => return Err(format!("Line {}: {e}", index + 1).into()),
+            }
+        }
+
+        Ok(tasks)
+    }
+
+    // Tasks are grouped by their project (or into the default project),
+    // deleted tasks and the templates of the recurring ones are skipped
+    pub fn import(content: &str, default_title: &str) -> Result<Vec<Project>, Box<dyn Error>> {
+        let mut projects: Vec<Project> = vec![];
+        let mut errors: Vec<String> = vec![];
+
+        for (index, imported) in Taskwarrior::parse(content)?.into_iter().enumerate() {
+            let number = index + 1;
+
+            let status = match imported.status.as_str() {
+                "pending" | "waiting" if imported.start.is_some() => TASK_STATUS_ON_GOING,
+                "pending" | "waiting" => TASK_STATUS_UP_NEXT,
+                "completed" => TASK_STATUS_DONE,
+                "deleted" | "recurring" => continue,
+                status => {
+                    errors.push(format!("Task {number}: unknown status \"{status}\""));
+                    continue;
+                }
+            };
+
+            let priority = match imported.priority.as_deref() {
+                None | Some("") => 0,
+                Some(value) => match PRIORITY_LETTERS.iter().find(|(l, _)| *l == value) {
+                    Some((_, priority)) => *priority,
+                    None => {
+                        errors.push(format!("Task {number}: unknown priority \"{value}\""));
+                        continue;
+                    }
+                },
+            };
+
+            if imported.description.trim().is_empty() {
+                errors.push(format!("Task {number}: empty description"));
+                continue;
+            }
+
+            let task = Task {
+                id: None,
+                // Exported again as it is
+                uid: imported
+                    .uuid
+                    .map(|u| u.to_lowercase())
+                    .filter(|u| Taskwarrior::is_uuid(u)),
+                title: imported.description.trim().to_string(),
+                status: status.to_string(),
+                priority: if status == TASK_STATUS_DONE {
+                    0
+                } else {
+                    priority
+                },
+                description: imported
+                    .annotations
+                    .iter()
+                    .map(|a| a.description.clone())
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+                tags: imported.tags,
+                created_at: imported.entry.as_deref().and_then(Interop::parse_date),
+                updated_at: imported.modified.as_deref().and_then(Interop::parse_date),
+                completed_at: imported
+                    .end
+                    .as_deref()
+                    .and_then(Interop::parse_date)
+                    .filter(|_| status == TASK_STATUS_DONE),
+                due_date: imported
+                    .due
+                    .as_deref()
+                    .and_then(Interop::parse_date)
+                    .map(|d| d.with_timezone(&Local).date_naive()),
+                ..Default::default()
+            };
+
+            let project_title = imported
+                .project
+                .filter(|p| !p.is_empty())
+                .unwrap_or(default_title.to_string());
+
+            Interop::add_task(&mut projects, &project_title, task);
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.join("\n").into());
+        }
+
+        Ok(projects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_helpers::date;
+
+    fn get_projects() -> Vec<Project> {
+        let created_at = Interop::parse_date("20261001T080000Z");
+        let task = |uid: &str, title: &str, status: &str, priority: u8| Task {
+            uid: Some(uid.to_string()),
+            title: title.to_string(),
+            status: status.to_string(),
+            priority,
+            created_at,
+            updated_at: created_at,
+            ..Default::default()
+        };
+
+        vec![Project {
+            id: None,
+            title: "Home".to_string(),
+            tasks: vec![
+                Task {
+                    description: "first line\nsecond line".to_string(),
+                    tags: vec!["house".to_string()],
+                    due_date: Some(date("2026-10-20")),
+                    ..task("00000000000000aa", "Paint", TASK_STATUS_UP_NEXT, 1)
+                },
+                task("0123456789abcdef", "Clean", TASK_STATUS_ON_GOING, 2),
+                Task {
+                    completed_at: created_at,
+                    ..task("é", "Shop", TASK_STATUS_DONE, 0)
+                },
+            ],
+        }]
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let exported = Taskwarrior::export(&get_projects());
+        let imported = Taskwarrior::import(&exported, "Default").unwrap();
+
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].title, "Home");
+
+        let paint = imported[0]
+            .tasks
+            .iter()
+            .find(|t| t.title == "Paint")
+            .unwrap();
+        assert_eq!(paint.status, TASK_STATUS_UP_NEXT);
+        assert_eq!(paint.priority, 1);
+        assert_eq!(paint.description, "first line\nsecond line");
+        assert_eq!(paint.tags, vec!["house"]);
+        assert_eq!(paint.due_date, Some(date("2026-10-20")));
+
+        let clean = imported[0]
+            .tasks
+            .iter()
+            .find(|t| t.title == "Clean")
+            .unwrap();
+        assert_eq!(clean.status, TASK_STATUS_ON_GOING);
+
+        // The uuids don't change on the next export
+        assert_eq!(Taskwarrior::export(&imported), exported);
+    }
+
+    #[test]
+    fn uuid_of_any_uid() {
+        for uid in ["é", "0123456789abcdef", "not an uuid-at-all"] {
+            let task = Task {
+                uid: Some(uid.to_string()),
+                ..Default::default()
+            };
+            let uuid = Taskwarrior::get_uuid(&task);
+
+            assert!(Taskwarrior::is_uuid(&uuid), "{uid}: {uuid}");
+            assert_eq!(Taskwarrior::get_uuid(&task), uuid);
+        }
+
+        let task = Task {
+            uid: Some("0e9c29ac-3f4c-4b8d-9d7a-bc1e0b5b4f11".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            Taskwarrior::get_uuid(&task),
+            "0e9c29ac-3f4c-4b8d-9d7a-bc1e0b5b4f11"
+        );
+    }
+
+    #[test]
+    fn import_keeps_the_uuids() {
+        let content = r#"[
+            {"uuid": "0E9C29AC-3F4C-4B8D-9D7A-BC1E0B5B4F11", "description": "A", "status": "pending"},
+            {"uuid": "invalid", "description": "B", "status": "pending"}
+        ]"#;
+        let projects = Taskwarrior::import(content, "Default").unwrap();
+
+        assert_eq!(
+            projects[0].tasks[0].uid.as_deref(),
+            Some("0e9c29ac-3f4c-4b8d-9d7a-bc1e0b5b4f11")
+        );
+        assert_eq!(projects[0].tasks[1].uid, None);
+    }
+
+    #[test]
+    fn import_statuses_and_line_format() {
+        let content = r#"{"description": "Started", "status": "pending", "start": "20261001T080000Z", "priority": "H"}
+{"description": "Waiting", "status": "waiting", "project": "Work"},
+{"description": "Done", "status": "completed", "priority": "L", "end": "20261002T080000Z"}
+{"description": "Removed", "status": "deleted"}
+{"description": "Template", "status": "recurring"}
+"#;
+        let projects = Taskwarrior::import(content, "Default").unwrap();
+        let titles: Vec<(&str, &str)> = projects
+            .iter()
+            .flat_map(|p| p.tasks.iter().map(|t| (p.title.as_str(), t.title.as_str())))
+            .collect();
+
+        assert_eq!(
+            titles,
+            vec![
+                ("Default", "Started"),
+                ("Default", "Done"),
+                ("Work", "Waiting")
+            ]
+        );
+
+        let tasks = &projects[0].tasks;
+        assert_eq!(tasks[0].status, TASK_STATUS_ON_GOING);
+        assert_eq!(tasks[0].priority, 1);
+        assert_eq!(tasks[1].status, TASK_STATUS_DONE);
+        assert_eq!(tasks[1].priority, 0);
+        assert!(tasks[1].completed_at.is_some());
+        assert_eq!(projects[1].tasks[0].status, TASK_STATUS_UP_NEXT);
+    }
+
+    #[test]
+    fn import_reports_the_invalid_tasks() {
+        let content = r#"[
+            {"description": "A", "status": "unknown"},
+            {"description": "B", "status": "pending", "priority": "X"},
+            {"description": " ", "status": "pending"}
+        ]"#;
+        let error = Taskwarrior::import(content, "Default")
+            .unwrap_err()
+            .to_string();
+
+        assert_eq!(
+            error,
+            "Task 1: unknown status \"unknown\"\nTask 2: unknown priority \"X\"\nTask 3: empty description"
+        );
+        assert!(Taskwarrior::import("{not json", "Default").is_err());
+    }
+}
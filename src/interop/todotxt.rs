@@ -0,0 +1,328 @@
+use std::error::Error;
+
+use chrono::NaiveDate;
+
+use crate::{
+    interop::Interop,
+    project::Project,
+    task::{Task, TASK_STATUSES, TASK_STATUS_DONE, TASK_STATUS_UP_NEXT},
+};
+
+// cf. https://github.com/todotxt/todo.txt
+pub struct TodoTxt;
+
+// Priorities of todo.txt in the same order of `TASK_PRIORITIES`
+static PRIORITY_LETTERS: [(char, u8); 3] = [('A', 1), ('B', 2), ('C', 3)];
+
+// todo.txt has no "OnGoing" status, it is kept in this extra
+static STATUS_KEY: &str = "status:";
+// Due date extra of the todo.txt apps: "due:2024-01-31"
+static DUE_KEY: &str = "due:";
+
+impl TodoTxt {
+    // One line per task: "x (A) Title @context key:value +Project due:2024-01-31 status:OnGoing".
+    // Contexts and extras are part of the title in basilk, so they are kept as they are
+    pub fn export(projects: &[Project]) -> String {
+        let mut lines: Vec<String> = vec![];
+
+        for project in projects {
+            let mut tasks = project.tasks.clone();
+            Task::sort(&mut tasks);
+
+            for task in tasks.iter() {
+                let mut words: Vec<String> = vec![];
+
+                if task.status == TASK_STATUS_DONE {
+                    words.push("x".to_string());
+                }
+
+                if let Some((letter, _)) =
+                    PRIORITY_LETTERS.iter().find(|(_, p)| *p == task.priority)
+                {
+                    words.push(format!("({letter})"));
+                }
+
+                words.push(task.title.clone());
+                words.push(format!("+{}", TodoTxt::get_project_tag(&project.title)));
+
+                if let Some(due_date) = task.due_date {
+                    words.push(format!("{DUE_KEY}{}", due_date.format("%Y-%m-%d")));
+                }
+
+                if task.status != TASK_STATUS_DONE && task.status != TASK_STATUS_UP_NEXT {
+                    words.push(format!("{STATUS_KEY}{}", task.status));
+                }
+
+                lines.push(words.join(" "));
+            }
+        }
+
+        lines.into_iter().map(|l| l + "\n").collect()
+    }
+
+    // Projects can't contain spaces: "My project" <=> "+My_project"
+    fn get_project_tag(title: &str) -> String {
+        title.split_whitespace().collect::<Vec<&str>>().join("_")
+    }
+
+    fn get_project_title(tag: &str) -> String {
+        tag.replace('_', " ")
+    }
+
+    // "(A)" to "(Z)"
+    fn is_priority(word: &str) -> bool {
+        let chars: Vec<char> = word.chars().collect();
+
+        chars.len() == 3 && chars[0] == '(' && chars[1].is_ascii_uppercase() && chars[2] == ')'
+    }
+
+    fn is_date(word: &str) -> bool {
+        let parts: Vec<&str> = word.split('-').collect();
+
+        parts.len() == 3
+            && [4, 2, 2]
+                .into_iter()
+                .zip(parts.iter())
+                .all(|(len, part)| part.len() == len && part.chars().all(|c| c.is_ascii_digit()))
+    }
+
+    // The first +project is the project of the task (or the default one),
+    // completion and creation dates are skipped
+    pub fn import(content: &str, default_title: &str) -> Result<Vec<Project>, Box<dyn Error>> {
+        let mut projects: Vec<Project> = vec![];
+        let mut errors: Vec<String> = vec![];
+
+        for (index, line) in content.lines().enumerate() {
+            let mut words = line.split_whitespace().peekable();
+
+            if words.peek().is_none() {
+                continue;
+            }
+
+            let mut status = TASK_STATUS_UP_NEXT.to_string();
+            let mut priority = 0;
+
+            if words.next_if_eq(&"x").is_some() {
+                status = TASK_STATUS_DONE.to_string();
+            }
+
+            // The priority can be before or after the dates (i.e. "x (A) 2024-01-02 Title")
+            while let Some(word) = words.next_if(|w| TodoTxt::is_date(w) || TodoTxt::is_priority(w))
+            {
+                if TodoTxt::is_priority(word) {
+                    let letter = word.chars().nth(1).unwrap();
+
+                    // Letters after "C" are the lowest priority too
+                    priority = match PRIORITY_LETTERS.iter().find(|(l, _)| *l == letter) {
+                        Some((_, p)) => *p,
+                        None => PRIORITY_LETTERS.last().unwrap().1,
+                    };
+                }
+            }
+
+            let mut title_words: Vec<&str> = vec![];
+            let mut project_title: Option<String> = None;
+            let mut due_date: Option<NaiveDate> = None;
+            let mut invalid_due_date: Option<&str> = None;
+
+            for word in words {
+                if let Some(value) = word.strip_prefix(DUE_KEY) {
+                    match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                        Ok(date) if TodoTxt::is_date(value) => due_date = Some(date),
+                        _ => invalid_due_date = Some(value),
+                    }
+                    continue;
+                }
+
+                if let Some(value) = word.strip_prefix(STATUS_KEY) {
+                    if status != TASK_STATUS_DONE {
+                        status = value.to_string();
+                    }
+                    continue;
+                }
+
+                match word.strip_prefix('+') {
+                    Some(tag) if !tag.is_empty() && project_title.is_none() => {
+                        project_title = Some(TodoTxt::get_project_title(tag));
+                    }
+                    _ => title_words.push(word),
+                }
+            }
+
+            if !TASK_STATUSES.contains(&status.as_str()) {
+                errors.push(format!(
+                    "Line {}: unknown status \"{status}\", expected one of: {}",
+                    index + 1,
+                    TASK_STATUSES.join(", ")
+                ));
+                continue;
+            }
+
+            if let Some(value) = invalid_due_date {
+                errors.push(format!(
+                    "Line {}: invalid due date \"{value}\", expected YYYY-MM-DD",
+                    index + 1
+                ));
+                continue;
+            }
+
+            if title_words.is_empty() {
+                errors.push(format!("Line {}: empty title", index + 1));
+                continue;
+            }
+
+            let task = Task {
+                id: None,
+                title: title_words.join(" "),
+                priority: if status == TASK_STATUS_DONE {
+                    0
+                } else {
+                    priority
+                },
+                status,
+                due_date,
+                ..Default::default()
+            };
+
+            let project_title = project_title.unwrap_or(default_title.to_string());
+
+            Interop::add_task(&mut projects, &project_title, task);
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.join("\n").into());
+        }
+
+        Ok(projects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{task::TASK_STATUS_ON_GOING, util::test_helpers::date};
+
+    fn get_projects() -> Vec<Project> {
+        vec![
+            Project {
+                id: None,
+                title: "My home".to_string(),
+                tasks: vec![
+                    Task {
+                        title: "Call mom @phone".to_string(),
+                        status: TASK_STATUS_ON_GOING.to_string(),
+                        priority: 1,
+                        due_date: Some(date("2026-10-20")),
+                        ..Default::default()
+                    },
+                    Task {
+                        title: "Paint key:value".to_string(),
+                        status: TASK_STATUS_DONE.to_string(),
+                        ..Default::default()
+                    },
+                ],
+            },
+            Project {
+                id: None,
+                title: "Work".to_string(),
+                tasks: vec![Task {
+                    title: "Report".to_string(),
+                    status: TASK_STATUS_UP_NEXT.to_string(),
+                    priority: 3,
+                    ..Default::default()
+                }],
+            },
+        ]
+    }
+
+    fn get_fields(projects: &[Project]) -> Vec<(String, String, String, u8, Option<NaiveDate>)> {
+        projects
+            .iter()
+            .flat_map(|p| {
+                p.tasks.iter().map(|t| {
+                    (
+                        p.title.clone(),
+                        t.title.clone(),
+                        t.status.clone(),
+                        t.priority,
+                        t.due_date,
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn export_import_round_trip() {
+        let exported = TodoTxt::export(&get_projects());
+
+        assert_eq!(
+            exported,
+            "(A) Call mom @phone +My_home due:2026-10-20 status:OnGoing\n\
+             x Paint key:value +My_home\n\
+             (C) Report +Work\n"
+        );
+
+        let mut projects = get_projects();
+        for project in projects.iter_mut() {
+            Task::sort(&mut project.tasks);
+        }
+
+        assert_eq!(
+            get_fields(&TodoTxt::import(&exported, "Default").unwrap()),
+            get_fields(&projects)
+        );
+    }
+
+    #[test]
+    fn import_dates_priorities_and_projects() {
+        let content = "x (A) 2026-10-02 2026-10-01 Done task +Home\n\
+                       \n\
+                       (D) Low priority\n\
+                       Two +First +Second due:2026-10-20\n";
+
+        let projects = TodoTxt::import(content, "Default").unwrap();
+
+        assert_eq!(
+            get_fields(&projects),
+            [
+                (
+                    "Home".to_string(),
+                    "Done task".to_string(),
+                    TASK_STATUS_DONE.to_string(),
+                    0,
+                    None
+                ),
+                (
+                    "Default".to_string(),
+                    "Low priority".to_string(),
+                    TASK_STATUS_UP_NEXT.to_string(),
+                    3,
+                    None
+                ),
+                (
+                    "First".to_string(),
+                    "Two +Second".to_string(),
+                    TASK_STATUS_UP_NEXT.to_string(),
+                    0,
+                    Some(date("2026-10-20"))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn import_reports_the_invalid_lines() {
+        let content = "Fine\n\
+                       Unknown status:Later\n\
+                       (A) +Home\n\
+                       Late due:2026-02-30\n";
+
+        assert_eq!(
+            TodoTxt::import(content, "Default").unwrap_err().to_string(),
+            "Line 2: unknown status \"Later\", expected one of: UpNext, OnGoing, Done\n\
+             Line 3: empty title\n\
+             Line 4: invalid due date \"2026-02-30\", expected YYYY-MM-DD"
+        );
+    }
+}
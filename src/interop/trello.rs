@@ -0,0 +1,372 @@
+use std::{collections::HashMap, error::Error};
+
+use serde::Deserialize;
+use serde_json::from_str;
+
+use crate::{
+    project::Project,
+    task::{ChecklistItem, Task, TASK_STATUSES},
+};
+
+// Json export of a Trello board, or of a generic kanban board with the cards inside the lists
+// (i.e. {"title": "Board", "columns": [{"title": "Todo", "cards": [{"title": "Card"}]}]})
+pub struct Trello;
+
+#[derive(Deserialize)]
+struct Board {
+    #[serde(alias = "title")]
+    name: String,
+    #[serde(default, alias = "columns")]
+    lists: Vec<List>,
+    // Trello keeps cards and checklists next to the lists, linked by id
+    #[serde(default)]
+    cards: Vec<Card>,
+    #[serde(default)]
+    checklists: Vec<Checklist>,
+}
+
+#[derive(Deserialize)]
+struct List {
+    #[serde(default)]
+    id: String,
+    #[serde(alias = "title")]
+    name: String,
+    // Archived
+    #[serde(default)]
+    closed: bool,
+    #[serde(default, alias = "tasks")]
+    cards: Vec<Card>,
+}
+
+#[derive(Deserialize)]
+struct Card {
+    #[serde(default)]
+    id: String,
+    #[serde(alias = "title")]
+    name: String,
+    #[serde(default, alias = "description")]
+    desc: String,
+    #[serde(default, rename = "idList")]
+    id_list: String,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    labels: Vec<Label>,
+    #[serde(default)]
+    checklists: Vec<Checklist>,
+}
+
+// Trello labels are objects with a name and a colour, the generic ones are plain text
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Label {
+    Text(String),
+    Object {
+        #[serde(default)]
+        name: String,
+        #[serde(default)]
+        color: Option<String>,
+    },
+}
+
+#[derive(Deserialize)]
+struct Checklist {
+    #[serde(default, rename = "idCard")]
+    id_card: String,
+    #[serde(default, rename = "checkItems", alias = "items")]
+    check_items: Vec<CheckItem>,
+}
+
+#[derive(Deserialize)]
+struct CheckItem {
+    #[serde(alias = "title")]
+    name: String,
+    // Trello: "complete" or "incomplete"
+    #[serde(default)]
+    state: String,
+    #[serde(default, alias = "checked")]
+    done: bool,
+}
+
+impl Trello {
+    // Mapping between the lists of the board and the statuses,
+    // i.e. "To Do=UpNext,Doing=OnGoing" (lists named as a status don't need it)
+    pub fn parse_mapping(value: &str) -> Result<HashMap<String, String>, Box<dyn Error>> {
+        let mut mapping: HashMap<String, String> = HashMap::new();
+
+        for pair in value.split(',').filter(|p| !p.trim().is_empty()) {
+            let Some((list, status)) = pair.rsplit_once('=') else {
+                return Err(format!("Invalid mapping \"{pair}\", expected <list>=<status>").into());
+            };
+
+            let Some(status) = TASK_STATUSES
+                .iter()
+                .find(|s| s.eq_ignore_ascii_case(status.trim()))
+            else {
+                return Err(format!(
+                    "Unknown status \"{}\", expected one of: {}",
+                    status.trim(),
+                    TASK_STATUSES.join(", ")
+                )
+                .into());
+            };
+
+            mapping.insert(list.trim().to_string(), status.to_string());
+        }
+
+        Ok(mapping)
+    }
+
+    fn get_status(list: &str, mapping: &HashMap<String, String>) -> Option<String> {
+        if let Some(status) = mapping.get(list) {
+            return Some(status.clone());
+        }
+
+        TASK_STATUSES
+            .iter()
+            .find(|s| s.eq_ignore_ascii_case(list.trim()))
+            .map(|s| s.to_string())
+    }
+
+    // A file can contain a board or a list of boards
+    fn parse(content: &str) -> Result<Vec<Board>, Box<dyn Error>> {
+        if content.trim_start().starts_with('[') {
+            return Ok(from_str::<Vec<Board>>(content)?);
+        }
+
+        Ok(vec![from_str::<Board>(content)?])
+    }
+
+    // One project per board, archived lists and cards are skipped.
+    // Every list must have a status, the ones without are all reported
+    pub fn import(
+        content: &str,
+        mapping: &HashMap<String, String>,
+    ) -> Result<Vec<Project>, Box<dyn Error>> {
+        let mut projects: Vec<Project> = vec![];
+        let mut unmapped_lists: Vec<String> = vec![];
+
+        for board in Trello::parse(content)? {
+            let title = board.name.trim().to_string();
+
+            // Boards without cards are imported too, and boards with the same name are kept
+            // apart (cf. Interop::rename_existing)
+            let mut project = Project {
+                id: None,
+                title,
+                tasks: vec![],
+            };
+
+            for list in board.lists.iter().filter(|l| !l.closed) {
+                let Some(status) = Trello::get_status(&list.name, mapping) else {
+                    if !unmapped_lists.contains(&list.name) {
+                        unmapped_lists.push(list.name.clone());
+                    }
+                    continue;
+                };
+
+                let cards = list
+                    .cards
+                    .iter()
+                    .chain(board.cards.iter().filter(|c| c.id_list == list.id));
+
+                for card in cards.filter(|c| !c.closed && !c.name.trim().is_empty()) {
+                    let checklists = card
+                        .checklists
+                        .iter()
+                        .chain(board.checklists.iter().filter(|c| c.id_card == card.id));
+
+                    let task = Task {
+                        id: None,
+                        title: card.name.trim().to_string(),
+                        status: status.clone(),
+                        priority: 0,
+                        description: card.desc.trim().to_string(),
+                        tags: card
+                            .labels
+                            .iter()
+                            .filter_map(|l| match l {
+                                Label::Text(name) => Some(name.clone()),
+                                // Labels without a name are shown only by their colour
+                                Label::Object { name, color } if name.is_empty() => color.clone(),
+                                Label::Object { name, .. } => Some(name.clone()),
+                            })
+                            .collect(),
+                        checklist: checklists
+                            .flat_map(|c| c.check_items.iter())
+                            .map(|i| ChecklistItem {
+                                title: i.name.trim().to_string(),
+                                done: i.done || i.state == "complete",
+                            })
+                            .collect(),
+                        ..Default::default()
+                    };
+
+                    project.tasks.push(task);
+                }
+            }
+
+            projects.push(project);
+        }
+
+        if !unmapped_lists.is_empty() {
+            return Err(format!(
+                "Missing the status of the lists: {}\nUse --map \"<list>=<status>,...\" with one of: {}",
+                unmapped_lists.join(", "),
+                TASK_STATUSES.join(", ")
+            )
+            .into());
+        }
+
+        Ok(projects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        interop::Interop,
+        task::{TASK_STATUS_DONE, TASK_STATUS_ON_GOING, TASK_STATUS_UP_NEXT},
+    };
+
+    static TRELLO: &str = r#"{
+        "name": "Home",
+        "lists": [
+            {"id": "l1", "name": "To Do"},
+            {"id": "l2", "name": "done"},
+            {"id": "l3", "name": "Old", "closed": true}
+        ],
+        "cards": [
+            {"id": "c1", "name": "Paint", "desc": "the wall ", "idList": "l1",
+             "labels": [{"name": "house", "color": "red"}, {"name": "", "color": "blue"}]},
+            {"id": "c2", "name": "Archived", "idList": "l1", "closed": true},
+            {"id": "c3", "name": "Clean", "idList": "l2"},
+            {"id": "c4", "name": "Forgotten", "idList": "l3"}
+        ],
+        "checklists": [
+            {"idCard": "c1", "checkItems": [
+                {"name": "buy paint", "state": "complete"},
+                {"name": "brush", "state": "incomplete"}
+            ]}
+        ]
+    }"#;
+
+    fn get_mapping(value: &str) -> HashMap<String, String> {
+        Trello::parse_mapping(value).unwrap()
+    }
+
+    #[test]
+    fn parse_mapping() {
+        let mapping = get_mapping("To Do=upnext, Doing = OnGoing,");
+
+        assert_eq!(mapping.len(), 2);
+        assert_eq!(mapping["To Do"], TASK_STATUS_UP_NEXT);
+        assert_eq!(mapping["Doing"], TASK_STATUS_ON_GOING);
+
+        assert!(Trello::parse_mapping("To Do").is_err());
+        assert!(Trello::parse_mapping("To Do=Later").is_err());
+    }
+
+    #[test]
+    fn import_trello() {
+        let projects = Trello::import(TRELLO, &get_mapping("To Do=UpNext")).unwrap();
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].title, "Home");
+
+        let tasks = &projects[0].tasks;
+
+        assert_eq!(
+            tasks
+                .iter()
+                .map(|t| t.title.as_str())
+                .collect::<Vec<&str>>(),
+            ["Paint", "Clean"]
+        );
+        assert_eq!(tasks[0].status, TASK_STATUS_UP_NEXT);
+        assert_eq!(tasks[0].description, "the wall");
+        assert_eq!(tasks[0].tags, ["house", "blue"]);
+        assert_eq!(
+            tasks[0]
+                .checklist
+                .iter()
+                .map(|i| (i.title.as_str(), i.done))
+                .collect::<Vec<(&str, bool)>>(),
+            [("buy paint", true), ("brush", false)]
+        );
+        assert_eq!(tasks[1].status, TASK_STATUS_DONE);
+    }
+
+    #[test]
+    fn import_kanban() {
+        let content = r#"[
+            {"title": "Work", "columns": [
+                {"title": "OnGoing", "cards": [
+                    {"title": "Report", "description": "monthly", "labels": ["office"],
+                     "checklists": [{"items": [{"title": "draft", "checked": true}]}]}
+                ]}
+            ]},
+            {"title": "Empty"}
+        ]"#;
+
+        let projects = Trello::import(content, &HashMap::new()).unwrap();
+
+        assert_eq!(
+            projects
+                .iter()
+                .map(|p| p.title.as_str())
+                .collect::<Vec<&str>>(),
+            ["Work", "Empty"]
+        );
+
+        let task = &projects[0].tasks[0];
+
+        assert_eq!(task.title, "Report");
+        assert_eq!(task.status, TASK_STATUS_ON_GOING);
+        assert_eq!(task.description, "monthly");
+        assert_eq!(task.tags, ["office"]);
+        assert!(task.checklist[0].done);
+        assert!(projects[1].tasks.is_empty());
+    }
+
+    #[test]
+    fn boards_with_the_same_name() {
+        let content = r#"[
+            {"title": "Board", "columns": [{"title": "UpNext", "cards": [{"title": "First"}]}]},
+            {"title": "Board", "columns": [{"title": "UpNext", "cards": [{"title": "Second"}]}]}
+        ]"#;
+
+        let projects = Trello::import(content, &HashMap::new()).unwrap();
+        let existing = vec![Project {
+            id: None,
+            title: "Board".to_string(),
+            tasks: vec![],
+        }];
+
+        assert_eq!(
+            Interop::rename_existing(&[], projects.clone())
+                .iter()
+                .map(|p| (p.title.as_str(), p.tasks[0].title.as_str()))
+                .collect::<Vec<(&str, &str)>>(),
+            [("Board", "First"), ("Board (2)", "Second")]
+        );
+        assert_eq!(
+            Interop::rename_existing(&existing, projects)
+                .iter()
+                .map(|p| p.title.as_str())
+                .collect::<Vec<&str>>(),
+            ["Board (2)", "Board (3)"]
+        );
+    }
+
+    #[test]
+    fn import_reports_the_unmapped_lists() {
+        let error = Trello::import(TRELLO, &HashMap::new())
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.starts_with("Missing the status of the lists: To Do\n"));
+        assert!(Trello::import("{\"lists\": []}", &HashMap::new()).is_err());
+    }
+}
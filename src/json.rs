@@ -1,32 +1,72 @@
-use std::{
-    error::Error,
-    fs::{self, File},
-    io::Write,
-    path::{Path, PathBuf},
-    sync::Mutex,
-};
+use std::{env, error::Error, fs, path::PathBuf, sync::Mutex};
 
-use serde_json::{from_str, to_string, Value};
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_string, to_string_pretty, to_value, Value};
 
 use crate::{
     migration::{Migration, JSON_VERSIONS},
     project::Project,
+    store::Store,
 };
 
-pub struct Json;
+pub struct Json {
+    // Write the file indented and with the keys sorted, to be readable in a diff
+    pretty: bool,
+}
+
+// Content of the json file, the schema version of the projects is saved next to them
+#[derive(Deserialize, Serialize)]
+struct Document<T> {
+    version: String,
+    projects: T,
+}
 
 static DIR_CONFIG_NAME: &str = env!("CARGO_PKG_NAME");
-static VERSION: Mutex<String> = Mutex::new(String::new());
+static DIR_PATH_ENV: &str = "BASILK_DATA_DIR";
+static JSON_FILE_NAME: &str = "board";
+// Data directory passed with the `--data-dir` flag
+static DIR_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
 
 impl Json {
-    pub fn get_dir_path() -> PathBuf {
+    pub fn new(pretty: bool) -> Json {
+        Json { pretty }
+    }
+
+    pub fn get_default_dir_path() -> PathBuf {
         let mut path = dirs::config_dir().unwrap();
         path.push(DIR_CONFIG_NAME);
 
         return path;
     }
 
-    fn get_json_path(version: String) -> PathBuf {
+    pub fn set_dir_path(path: PathBuf) {
+        *DIR_PATH.lock().unwrap() = Some(path);
+    }
+
+    pub fn get_dir_path() -> PathBuf {
+        // Priority: `--data-dir` flag, `BASILK_DATA_DIR` env variable, default directory
+        if let Some(path) = DIR_PATH.lock().unwrap().clone() {
+            return path;
+        }
+
+        if let Some(path) = env::var_os(DIR_PATH_ENV).filter(|p| !p.is_empty()) {
+            return PathBuf::from(path);
+        }
+
+        Json::get_default_dir_path()
+    }
+
+    fn get_json_path() -> PathBuf {
+        let mut path = PathBuf::new();
+        path.push(Json::get_dir_path().as_path());
+        path.push(format!("{JSON_FILE_NAME}.json"));
+
+        return path;
+    }
+
+    // Until 0.2.x the version was the name of the file (i.e. "911fc.json")
+    // and the file contained only the list of projects
+    fn get_legacy_json_path(version: &str) -> PathBuf {
         let mut path = PathBuf::new();
         path.push(Json::get_dir_path().as_path());
         path.push(format!("{version}.json"));
@@ -34,81 +74,139 @@ impl Json {
         return path;
     }
 
-    pub fn check() -> Result<bool, Box<dyn Error>> {
-        fs::create_dir_all(Json::get_dir_path())?;
+    pub fn serialize<T: Serialize>(&self, value: &T) -> String {
+        if !self.pretty {
+            return to_string(value).unwrap();
+        }
 
-        // Create the state to save the json version
-        let mut version_state = VERSION.lock().unwrap();
+        // Passing through `Value` sorts the keys of every object
+        let value = to_value(value).unwrap();
+        format!("{}\n", to_string_pretty(&value).unwrap())
+    }
 
-        // Pick the version from the internal file
-        let mut json_version_from_file: Vec<&str> = JSON_VERSIONS
-            .into_iter()
-            .filter(|version| Path::new(&Json::get_json_path(version.to_string())).is_file())
-            .collect();
+    fn write_document<T: Serialize>(&self, document: &Document<T>) -> Result<(), Box<dyn Error>> {
+        fs::write(Json::get_json_path(), self.serialize(document))?;
 
-        // If the file doesn't exist create a new one with the last version
-        if json_version_from_file.is_empty() {
-            let last_json_version = JSON_VERSIONS.last().unwrap();
-            let path = Json::get_json_path(last_json_version.to_string());
+        Ok(())
+    }
 
-            let mut file = File::create(path).unwrap();
-            let _ = file.write_all(b"[]");
+    fn get_legacy_version() -> Option<&'static str> {
+        JSON_VERSIONS
+            .into_iter()
+            .find(|version| Json::get_legacy_json_path(version).is_file())
+    }
 
-            json_version_from_file = vec![last_json_version];
-            version_state.push_str(json_version_from_file[0]);
+    fn read_legacy_document(version: &str) -> Result<Document<Value>, Box<dyn Error>> {
+        let json_raw = fs::read_to_string(Json::get_legacy_json_path(version))?;
 
+        Ok(Document {
+            version: version.to_string(),
+            projects: from_str::<Value>(&json_raw)?,
+        })
+    }
+
+    // Move the legacy file into the stable one, keeping its version.
+    // Return true if a legacy file was found
+    fn move_legacy_file(&self) -> Result<bool, Box<dyn Error>> {
+        let Some(version) = Json::get_legacy_version() else {
             return Ok(false);
-        }
+        };
 
-        // Save into the internal state the last json version
-        version_state.push_str(json_version_from_file[0]);
+        self.write_document(&Json::read_legacy_document(version)?)?;
+        fs::remove_file(Json::get_legacy_json_path(version))?;
 
-        // Read the internal file
-        let path = Json::get_json_path(json_version_from_file[0].to_string());
-        let json_raw = fs::read_to_string(&path).unwrap();
-        let json = from_str::<Vec<Value>>(&json_raw).unwrap();
+        Ok(true)
+    }
 
-        if json.is_empty() {
-            return Ok(false);
-        }
+    pub fn check(&self) -> Result<bool, Box<dyn Error>> {
+        fs::create_dir_all(Json::get_dir_path())?;
+
+        let path = Json::get_json_path();
+        let last_json_version = JSON_VERSIONS.last().unwrap().to_string();
 
-        // Load all migrations
-        let migrations = Migration::get_migrations(json_version_from_file[0], json);
+        let was_moved_legacy_file = !path.is_file() && self.move_legacy_file()?;
+
+        // If the file doesn't exist create a new one with the last version
+        if !path.is_file() {
+            self.write(&[]);
 
-        if migrations.is_empty() {
             return Ok(false);
         }
 
-        // Loop thru all migrations and apply them!
-        for (version, migration) in migrations.iter() {
-            let path = Json::get_json_path(version_state.to_string());
-            let new_path = Json::get_json_path(version.to_string());
+        // Read the internal file
+        let json_raw = fs::read_to_string(&path)?;
+        let document = from_str::<Document<Value>>(&json_raw)?;
 
-            let new_json = migration;
+        if document.version == last_json_version {
+            return Ok(was_moved_legacy_file);
+        }
 
-            fs::write(&path, new_json).unwrap();
-            fs::rename(&path, new_path)?;
+        // Without projects there is nothing to migrate, only the version is updated
+        if document.projects.as_array().is_some_and(|p| p.is_empty()) {
+            self.write(&[]);
 
-            // Save into the internal state the json version of the last migration applied
-            version_state.clear();
-            version_state.push_str(&version)
+            return Ok(was_moved_legacy_file);
         }
 
+        // The file is rewritten in place with the result of all the migrations
+        let (version, projects) = Migration::migrate(&document.version, document.projects)?;
+
+        self.write_document(&Document {
+            version: version.to_string(),
+            projects,
+        })?;
+
         Ok(true)
     }
 
     pub fn read() -> Vec<Project> {
-        let version = VERSION.lock().unwrap().to_string();
-        let path = Json::get_json_path(version);
+        let json = fs::read_to_string(Json::get_json_path()).unwrap();
+
+        return from_str::<Document<Vec<Project>>>(&json).unwrap().projects;
+    }
+
+    pub fn write(&self, projects: &[Project]) {
+        self.write_document(&Document {
+            version: JSON_VERSIONS.last().unwrap().to_string(),
+            projects,
+        })
+        .unwrap();
+    }
+}
+
+// The whole file is rewritten on every change
+impl Store for Json {
+    fn check(&self) -> Result<bool, Box<dyn Error>> {
+        Json::check(self)
+    }
+
+    fn load(&self) -> Vec<Project> {
+        Json::read()
+    }
 
-        let json = fs::read_to_string(path).unwrap();
-        return from_str::<Vec<Project>>(&json).unwrap();
+    fn save_all(&self, projects: &[Project]) {
+        Json::write(self, projects)
     }
 
-    pub fn write(projects: Vec<Project>) {
-        let version = VERSION.lock().unwrap().to_string();
-        let path = Json::get_json_path(version);
+    fn get_pending_migration(&self) -> Result<Option<(Value, Value)>, Box<dyn Error>> {
+        let path = Json::get_json_path();
+
+        let document = if path.is_file() {
+            from_str::<Document<Value>>(&fs::read_to_string(path)?)?
+        } else {
+            match Json::get_legacy_version() {
+                Some(version) => Json::read_legacy_document(version)?,
+                None => return Ok(None),
+            }
+        };
+
+        if document.version == *JSON_VERSIONS.last().unwrap() {
+            return Ok(None);
+        }
+
+        let (_version, new_json) =
+            Migration::migrate(&document.version, document.projects.clone())?;
 
-        fs::write(path, to_string(&projects).unwrap()).unwrap();
+        Ok(Some((document.projects, new_json)))
     }
 }
@@ -1,13 +1,17 @@
 use std::{
     error::Error,
     fmt::Debug,
-    io::{self, stdout},
+    io::{self, stdout, Write},
+    time::{Duration, Instant},
 };
 
 use cli::Cli;
 use ratatui::{
     crossterm::{
-        event::{self, Event, KeyCode, KeyEventKind},
+        event::{
+            self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+            KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+        },
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
         ExecutableCommand,
     },
@@ -16,20 +20,36 @@ use ratatui::{
 };
 use tui_input::{backend::crossterm::EventHandler, Input};
 
+mod agenda;
+mod calendar;
 mod cli;
 mod config;
+mod directory;
+mod focus;
+mod interop;
 mod json;
 mod migration;
 mod project;
+mod recurrence;
+mod report;
+mod sqlite;
+mod statistics;
+mod store;
 mod task;
 mod ui;
 mod util;
 mod view;
 
+use agenda::Agenda;
+use calendar::Calendar;
+use chrono::{Local, NaiveDate};
 use config::{Config, ConfigToml};
-use json::Json;
+use focus::{Focus, FocusPhase};
+use interop::{markdown::Markdown, Interop};
 use project::Project;
+use store::Store;
 use task::{Task, TASK_PRIORITIES, TASK_STATUSES};
+use util::Util;
 use view::View;
 
 #[derive(Default, PartialEq, Debug)]
@@ -39,31 +59,81 @@ pub enum ViewMode {
     RenameProject,
     AddProject,
     DeleteProject,
+    ViewStatistics,
+    ViewAgenda,
+    ViewCalendar,
 
     ViewTasks,
     RenameTask,
     ChangeStatusTask,
     ChangePriorityTask,
+    ChangeDueDateTask,
+    ChangeRecurrenceTask,
+    ChangeBlockersTask,
+    ViewTaskDependencies,
+    MoveTasks,
+    ArchiveTasks,
     AddTask,
     DeleteTask,
+    ViewTaskHistory,
+    FocusTask,
 
     InfoMigration,
 }
 
+// Max time waiting for a key before redrawing (i.e. to update the running timers)
+static TICK_RATE: Duration = Duration::from_millis(250);
+// Max time between the clicks of a double click
+static DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+
+// Lists shown in the modals
+#[derive(Default)]
+struct ModalItems<'a> {
+    status: Vec<ListItem<'a>>,
+    priority: Vec<ListItem<'a>>,
+    // Loaded when the blockers of a task are shown, to move through them
+    blocker: Vec<ListItem<'a>>,
+    // Loaded when the tasks are moved to another project
+    project: Vec<ListItem<'a>>,
+}
+
 pub struct App {
     // TODO: Better list state mgmt
     selected_project_index: ListState,
     selected_task_index: ListState,
     selected_status_task_index: ListState,
     selected_priority_task_index: ListState,
+    selected_blocker_task_index: ListState,
+    selected_agenda_index: ListState,
+    selected_move_project_index: ListState,
+    // Positions of the tasks marked for the bulk actions, and of the last one (un)marked
+    marked_tasks: Vec<usize>,
+    mark_anchor: Option<usize>,
+    // Areas of the last rendered list and modal, to find the clicked item
+    list_area: Rect,
+    modal_area: Rect,
+    // Time and item of the last click, for the double click
+    last_click: Option<(Instant, usize)>,
+    // The task actions were opened from the agenda, go back to it once done
+    is_agenda_open: bool,
+    // Selected day of the calendar
+    calendar_date: NaiveDate,
     view_mode: ViewMode,
     projects: Vec<Project>,
     config: ConfigToml,
+    store: Box<dyn Store>,
+    // Message shown in a modal over the current view, closed by any key
+    info_message: Option<String>,
+    // Countdown of the focus mode, while the selected task is focused
+    focus: Option<Focus>,
 }
 
-fn init_terminal() -> Result<Terminal<impl Backend>, Box<dyn Error>> {
+fn init_terminal(mouse: bool) -> Result<Terminal<impl Backend>, Box<dyn Error>> {
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
+    if mouse {
+        stdout().execute(EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout());
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
@@ -71,6 +141,7 @@ fn init_terminal() -> Result<Terminal<impl Backend>, Box<dyn Error>> {
 
 fn restore_terminal() -> Result<(), Box<dyn Error>> {
     disable_raw_mode()?;
+    stdout().execute(DisableMouseCapture)?;
     stdout().execute(LeaveAlternateScreen)?;
     Ok(())
 }
@@ -78,14 +149,17 @@ fn restore_terminal() -> Result<(), Box<dyn Error>> {
 fn main() -> Result<(), Box<dyn Error>> {
     Cli::read();
 
-    // setup terminal
-    let terminal = init_terminal()?;
+    let config = Config::read();
+    let store = config.storage.open()?;
+
+    // Check the version of the storage, before the terminal setup to show the errors
+    let were_applied_migrations = store.check()?;
 
-    // Check the version of the json file
-    let were_applied_migrations = Json::check()?;
+    // setup terminal
+    let terminal = init_terminal(config.ui.mouse)?;
 
     // create app and run it
-    App::setup().run(terminal, were_applied_migrations)?;
+    App::setup(config, store).run(terminal, were_applied_migrations)?;
 
     restore_terminal()?;
 
@@ -93,15 +167,28 @@ fn main() -> Result<(), Box<dyn Error>> {
 }
 
 impl App {
-    fn setup() -> Self {
+    fn setup(config: ConfigToml, store: Box<dyn Store>) -> Self {
         Self {
             selected_project_index: ListState::default().with_selected(Some(0)),
             selected_task_index: ListState::default().with_selected(Some(0)),
             selected_status_task_index: ListState::default().with_selected(Some(0)),
             selected_priority_task_index: ListState::default().with_selected(Some(0)),
+            selected_blocker_task_index: ListState::default().with_selected(Some(0)),
+            selected_agenda_index: ListState::default().with_selected(Some(0)),
+            selected_move_project_index: ListState::default().with_selected(Some(0)),
+            marked_tasks: vec![],
+            mark_anchor: None,
+            list_area: Rect::default(),
+            modal_area: Rect::default(),
+            last_click: None,
+            is_agenda_open: false,
+            calendar_date: Local::now().date_naive(),
             view_mode: ViewMode::default(),
-            projects: Json::read(),
-            config: Config::read(),
+            projects: store.load(),
+            config,
+            store,
+            info_message: None,
+            focus: None,
         }
     }
 
@@ -115,24 +202,40 @@ impl App {
         let mut items: Vec<ListItem> = vec![];
         Project::load_items(self, &mut items);
 
-        let mut status_items: Vec<ListItem> = vec![];
-        Task::load_statues_items(&mut status_items);
-
-        let mut priority_items: Vec<ListItem> = vec![];
-        Task::load_priority_items(&mut priority_items);
+        let mut modal_items = ModalItems::default();
+        Task::load_statues_items(&mut modal_items.status);
+        Task::load_priority_items(&mut modal_items.priority);
 
         if were_applied_migrations {
             self.view_mode = ViewMode::InfoMigration
         }
 
         loop {
-            terminal.draw(|f| {
-                self.render(f, f.size(), &input, &items, &status_items, &priority_items)
-            })?;
+            terminal.draw(|f| self.render(f, f.size(), &input, &items, &modal_items))?;
+
+            if !event::poll(TICK_RATE)? {
+                self.refresh_timers(&mut items);
+                self.refresh_focus(&mut items);
+                continue;
+            }
+
+            // Mouse actions are handled as the keys doing the same
+            let event = match event::read()? {
+                Event::Mouse(mouse) => match self.get_mouse_key(mouse, &items) {
+                    Some(code) => Event::Key(KeyEvent::new(code, KeyModifiers::NONE)),
+                    None => continue,
+                },
+                event => event,
+            };
 
-            if let Event::Key(key) = event::read()? {
+            if let Event::Key(key) = event {
                 // Capture only the "Press" event to prevent double input on Windows
                 if key.kind == KeyEventKind::Press {
+                    if self.info_message.is_some() {
+                        self.info_message = None;
+                        continue;
+                    }
+
                     use KeyCode::*;
                     match self.view_mode {
                         ViewMode::ViewProjects => match key.code {
@@ -169,6 +272,23 @@ impl App {
 
                                 App::change_view(self, ViewMode::DeleteProject);
                             }
+                            Char('e') => {
+                                self.export(&self.projects.clone());
+                            }
+                            Char('s') => {
+                                App::change_view(self, ViewMode::ViewStatistics);
+                            }
+                            Char('c') => {
+                                self.calendar_date = Local::now().date_naive();
+
+                                App::change_view(self, ViewMode::ViewCalendar);
+                            }
+                            Char('a') => {
+                                self.is_agenda_open = true;
+                                Agenda::load_items(self, &mut items);
+
+                                App::change_view(self, ViewMode::ViewAgenda);
+                            }
                             Down | Tab | Char('j') => {
                                 self.next(&items);
                             }
@@ -211,6 +331,15 @@ impl App {
                                 input.handle_event(&Event::Key(key));
                             }
                         },
+                        ViewMode::ViewStatistics => match key.code {
+                            Esc | Char('s') => {
+                                App::change_view(self, ViewMode::ViewProjects);
+                            }
+                            Char('q') => {
+                                return Ok(());
+                            }
+                            _ => {}
+                        },
                         ViewMode::DeleteProject => match key.code {
                             Char('y') => {
                                 Project::delete(self, &mut items);
@@ -224,12 +353,145 @@ impl App {
                             _ => {}
                         },
 
+                        ViewMode::ViewCalendar => match key.code {
+                            Left | Char('h') => {
+                                Calendar::move_days(self, -1);
+                            }
+                            Right | Char('l') => {
+                                Calendar::move_days(self, 1);
+                            }
+                            Up | Char('k') => {
+                                Calendar::move_days(self, -7);
+                            }
+                            Down | Char('j') => {
+                                Calendar::move_days(self, 7);
+                            }
+                            PageUp | Char('[') => {
+                                Calendar::move_months(self, -1);
+                            }
+                            PageDown | Char(']') => {
+                                Calendar::move_months(self, 1);
+                            }
+                            Char('t') => {
+                                self.calendar_date = Local::now().date_naive();
+                            }
+                            Esc | Char('c') => {
+                                App::change_view(self, ViewMode::ViewProjects);
+                            }
+                            Char('q') => {
+                                return Ok(());
+                            }
+                            _ => {}
+                        },
+                        ViewMode::ViewAgenda => match key.code {
+                            Esc | Char('a') => {
+                                self.is_agenda_open = false;
+                                Project::load_items(self, &mut items);
+
+                                App::change_view(self, ViewMode::ViewProjects);
+                            }
+                            Enter => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                Agenda::select_task(self);
+                                let index = TASK_STATUSES
+                                    .into_iter()
+                                    .position(|t| t == Task::get_current(self).status)
+                                    .unwrap();
+
+                                self.selected_status_task_index.select(Some(index));
+
+                                App::change_view(self, ViewMode::ChangeStatusTask);
+                            }
+                            Char('p') => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                Agenda::select_task(self);
+                                let index = TASK_PRIORITIES
+                                    .into_iter()
+                                    .position(|t| t == Task::get_current(self).priority)
+                                    .unwrap();
+
+                                self.selected_priority_task_index.select(Some(index));
+
+                                App::change_view(self, ViewMode::ChangePriorityTask);
+                            }
+                            Char('r') => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                Agenda::select_task(self);
+                                input = input
+                                    .clone()
+                                    .with_value(Task::get_current(self).title.clone());
+
+                                App::change_view(self, ViewMode::RenameTask);
+                            }
+                            Down | Tab | Char('j') if !items.is_empty() => {
+                                self.next(&items);
+                            }
+                            Up | BackTab | Char('k') if !items.is_empty() => {
+                                self.previous(&items);
+                            }
+                            Char('q') => {
+                                return Ok(());
+                            }
+                            _ => {}
+                        },
                         ViewMode::ViewTasks => match key.code {
+                            Esc if !self.marked_tasks.is_empty() => {
+                                Task::clear_marks(self);
+                                Task::load_items(self, &mut items);
+                            }
                             Esc | Left | Char('h') => {
+                                Task::clear_marks(self);
                                 Project::load_items(self, &mut items);
 
                                 App::change_view(self, ViewMode::ViewProjects);
                             }
+                            Char(' ') => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                Task::toggle_mark(self, &mut items);
+                            }
+                            Char('V') => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                Task::mark_range(self, &mut items);
+                            }
+                            Char('m') => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                Task::load_move_items(self, &mut modal_items.project);
+
+                                if modal_items.project.is_empty() {
+                                    self.info_message =
+                                        Some("There are no other projects".to_string());
+                                    continue;
+                                }
+
+                                self.selected_move_project_index.select(Some(0));
+
+                                App::change_view(self, ViewMode::MoveTasks);
+                            }
+                            Char('A') => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                App::change_view(self, ViewMode::ArchiveTasks);
+                            }
                             Enter => {
                                 if items.is_empty() {
                                     continue;
@@ -269,6 +531,33 @@ impl App {
 
                                 App::change_view(self, ViewMode::RenameTask);
                             }
+                            Char('D') => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                let due_date = Task::get_current(self)
+                                    .due_date
+                                    .map(|d| d.to_string())
+                                    .unwrap_or_default();
+                                input = input.clone().with_value(due_date);
+
+                                App::change_view(self, ViewMode::ChangeDueDateTask);
+                            }
+                            Char('R') => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                let recurrence = Task::get_current(self)
+                                    .recurrence
+                                    .as_ref()
+                                    .map(|r| r.get_repr())
+                                    .unwrap_or_default();
+                                input = input.clone().with_value(recurrence);
+
+                                App::change_view(self, ViewMode::ChangeRecurrenceTask);
+                            }
                             Char('n') => {
                                 input.reset();
 
@@ -281,6 +570,49 @@ impl App {
 
                                 App::change_view(self, ViewMode::DeleteTask);
                             }
+                            Char('t') => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                Task::toggle_timer(self, &mut items);
+                            }
+                            Char('H') => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                App::change_view(self, ViewMode::ViewTaskHistory);
+                            }
+                            Char('b') => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                Task::load_blocker_items(self, &mut modal_items.blocker);
+                                self.selected_blocker_task_index.select(Some(0));
+
+                                App::change_view(self, ViewMode::ChangeBlockersTask);
+                            }
+                            Char('B') => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                App::change_view(self, ViewMode::ViewTaskDependencies);
+                            }
+                            Char('f') => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                self.focus = Some(Focus::new(FocusPhase::Work));
+                                App::change_view(self, ViewMode::FocusTask);
+                            }
+                            Char('e') => {
+                                let project = Project::get_current(self).clone();
+                                self.export(&[project]);
+                            }
                             Down | Tab | Char('j') => {
                                 self.next(&items);
                             }
@@ -292,66 +624,183 @@ impl App {
                             }
                             _ => {}
                         },
+                        ViewMode::ViewTaskHistory => match key.code {
+                            Esc | Char('H') => {
+                                App::change_view(self, ViewMode::ViewTasks);
+                            }
+                            Char('q') => {
+                                return Ok(());
+                            }
+                            _ => {}
+                        },
+                        ViewMode::ChangeBlockersTask => match key.code {
+                            Enter | Char(' ') => {
+                                if modal_items.blocker.is_empty() {
+                                    continue;
+                                }
+
+                                Task::toggle_blocker(
+                                    self,
+                                    &mut items,
+                                    self.selected_blocker_task_index.selected().unwrap(),
+                                );
+                                Task::load_blocker_items(self, &mut modal_items.blocker);
+                            }
+                            Down | Tab | Char('j') if !modal_items.blocker.is_empty() => {
+                                self.next(&modal_items.blocker);
+                            }
+                            Up | BackTab | Char('k') if !modal_items.blocker.is_empty() => {
+                                self.previous(&modal_items.blocker);
+                            }
+                            Esc | Char('b') => {
+                                App::change_view(self, ViewMode::ViewTasks);
+                            }
+                            _ => {}
+                        },
+                        ViewMode::MoveTasks => match key.code {
+                            Enter => {
+                                let to_project_index = Task::get_move_projects(self)
+                                    [self.selected_move_project_index.selected().unwrap()];
+                                Task::move_marked(self, &mut items, to_project_index);
+
+                                App::change_view(self, ViewMode::ViewTasks);
+                            }
+                            Down | Tab | Char('j') => {
+                                self.next(&modal_items.project);
+                            }
+                            Up | BackTab | Char('k') => {
+                                self.previous(&modal_items.project);
+                            }
+                            Esc => {
+                                App::change_view(self, ViewMode::ViewTasks);
+                            }
+                            _ => {}
+                        },
+                        ViewMode::ArchiveTasks => match key.code {
+                            Char('y') => {
+                                Task::archive_marked(self, &mut items);
+
+                                App::change_view(self, ViewMode::ViewTasks);
+                            }
+                            Char('n') => {
+                                App::change_view(self, ViewMode::ViewTasks);
+                            }
+                            _ => {}
+                        },
+                        ViewMode::ViewTaskDependencies => match key.code {
+                            Esc | Char('B') => {
+                                App::change_view(self, ViewMode::ViewTasks);
+                            }
+                            Char('q') => {
+                                return Ok(());
+                            }
+                            _ => {}
+                        },
+                        ViewMode::FocusTask => match key.code {
+                            // An interrupted session is not logged
+                            Esc | Char('f') => {
+                                self.focus = None;
+                                App::change_view(self, ViewMode::ViewTasks);
+                            }
+                            Char(' ') => {
+                                self.focus = Some(Focus::new(FocusPhase::Work));
+                            }
+                            Char('q') => {
+                                return Ok(());
+                            }
+                            _ => {}
+                        },
                         ViewMode::RenameTask => match key.code {
                             Enter => {
                                 Task::rename(self, &mut items, input.value());
                                 input.reset();
 
-                                App::change_view(self, ViewMode::ViewTasks);
+                                self.close_task_modal(&mut items);
                             }
                             Esc => {
                                 input.reset();
 
-                                App::change_view(self, ViewMode::ViewTasks);
+                                self.close_task_modal(&mut items);
                             }
                             _ => {
                                 input.handle_event(&Event::Key(key));
                             }
                         },
+                        ViewMode::ChangeDueDateTask | ViewMode::ChangeRecurrenceTask => {
+                            match key.code {
+                                Enter => {
+                                    let result = if self.view_mode == ViewMode::ChangeDueDateTask {
+                                        Task::change_due_date(self, &mut items, input.value())
+                                    } else {
+                                        Task::change_recurrence(self, &mut items, input.value())
+                                    };
+
+                                    if let Err(e) = result {
+                                        self.info_message = Some(e);
+                                    }
+
+                                    input.reset();
+
+                                    self.close_task_modal(&mut items);
+                                }
+                                Esc => {
+                                    input.reset();
+
+                                    self.close_task_modal(&mut items);
+                                }
+                                _ => {
+                                    input.handle_event(&Event::Key(key));
+                                }
+                            }
+                        }
                         ViewMode::ChangeStatusTask => match key.code {
                             Enter => {
-                                Task::change_status(
-                                    self,
-                                    &mut items,
-                                    TASK_STATUSES
-                                        [self.selected_status_task_index.selected().unwrap()],
-                                );
+                                let status = TASK_STATUSES
+                                    [self.selected_status_task_index.selected().unwrap()];
+
+                                if self.marked_tasks.is_empty() {
+                                    Task::change_status(self, &mut items, status);
+                                } else {
+                                    Task::change_status_marked(self, &mut items, status);
+                                }
 
                                 self.selected_status_task_index.select(Some(0));
-                                App::change_view(self, ViewMode::ViewTasks);
+                                self.close_task_modal(&mut items);
                             }
 
                             Down | BackTab | Char('j') => {
-                                self.next(&status_items);
+                                self.next(&modal_items.status);
                             }
                             Up | Tab | Char('k') => {
-                                self.previous(&status_items);
+                                self.previous(&modal_items.status);
                             }
                             Esc => {
-                                App::change_view(self, ViewMode::ViewTasks);
+                                self.close_task_modal(&mut items);
                             }
                             _ => {}
                         },
                         ViewMode::ChangePriorityTask => match key.code {
                             Enter => {
-                                Task::change_priority(
-                                    self,
-                                    &mut items,
-                                    TASK_PRIORITIES
-                                        [self.selected_priority_task_index.selected().unwrap()],
-                                );
+                                let priority = TASK_PRIORITIES
+                                    [self.selected_priority_task_index.selected().unwrap()];
+
+                                if self.marked_tasks.is_empty() {
+                                    Task::change_priority(self, &mut items, priority);
+                                } else {
+                                    Task::change_priority_marked(self, &mut items, priority);
+                                }
 
                                 self.selected_priority_task_index.select(Some(0));
-                                App::change_view(self, ViewMode::ViewTasks);
+                                self.close_task_modal(&mut items);
                             }
                             Down | BackTab | Char('j') => {
-                                self.next(&priority_items);
+                                self.next(&modal_items.priority);
                             }
                             Up | Tab | Char('k') => {
-                                self.previous(&priority_items);
+                                self.previous(&modal_items.priority);
                             }
                             Esc => {
-                                App::change_view(self, ViewMode::ViewTasks);
+                                self.close_task_modal(&mut items);
                             }
                             _ => {}
                         },
@@ -370,7 +819,11 @@ impl App {
                         },
                         ViewMode::DeleteTask => match key.code {
                             Char('y') => {
-                                Task::delete(self, &mut items);
+                                if self.marked_tasks.is_empty() {
+                                    Task::delete(self, &mut items);
+                                } else {
+                                    Task::delete_marked(self, &mut items);
+                                }
                                 self.selected_task_index.select_previous();
 
                                 App::change_view(self, ViewMode::ViewTasks);
@@ -398,8 +851,7 @@ impl App {
         area: Rect,
         input: &Input,
         items: &Vec<ListItem>,
-        status_items: &Vec<ListItem>,
-        priority_items: &Vec<ListItem>,
+        modal_items: &ModalItems,
     ) {
         let layout = Layout::vertical(if self.config.ui.show_help {
             [
@@ -427,7 +879,15 @@ impl App {
         );
 
         // Main view
-        View::show_items(self, items, f, main_area);
+        if self.view_mode == ViewMode::ViewStatistics {
+            View::show_statistics(self, f, main_area);
+        } else if self.view_mode == ViewMode::ViewCalendar {
+            View::show_calendar(self, f, main_area);
+        } else if self.view_mode == ViewMode::FocusTask {
+            View::show_focus(self, f, main_area);
+        } else {
+            View::show_items(self, items, f, main_area);
+        }
 
         // Other views
         if self.view_mode == ViewMode::InfoMigration {
@@ -442,16 +902,48 @@ impl App {
             View::show_rename_item_modal(f, area, input)
         }
 
+        if self.view_mode == ViewMode::ChangeDueDateTask {
+            View::show_due_date_modal(f, area, input)
+        }
+
+        if self.view_mode == ViewMode::ChangeRecurrenceTask {
+            View::show_recurrence_modal(f, area, input)
+        }
+
         if self.view_mode == ViewMode::DeleteTask || self.view_mode == ViewMode::DeleteProject {
             View::show_delete_item_modal(self, f, area)
         }
 
         if self.view_mode == ViewMode::ChangeStatusTask {
-            View::show_select_task_status_modal(self, status_items, f, area)
+            View::show_select_task_status_modal(self, &modal_items.status, f, area)
         }
 
         if self.view_mode == ViewMode::ChangePriorityTask {
-            View::show_select_task_priority_modal(self, priority_items, f, area)
+            View::show_select_task_priority_modal(self, &modal_items.priority, f, area)
+        }
+
+        if self.view_mode == ViewMode::ChangeBlockersTask {
+            View::show_select_task_blockers_modal(self, &modal_items.blocker, f, area)
+        }
+
+        if self.view_mode == ViewMode::MoveTasks {
+            View::show_move_tasks_modal(self, &modal_items.project, f, area)
+        }
+
+        if self.view_mode == ViewMode::ArchiveTasks {
+            View::show_archive_tasks_modal(self, f, area)
+        }
+
+        if self.view_mode == ViewMode::ViewTaskDependencies {
+            View::show_task_dependencies_modal(self, f, area)
+        }
+
+        if self.view_mode == ViewMode::ViewTaskHistory {
+            View::show_task_history_modal(self, f, area)
+        }
+
+        if let Some(message) = &self.info_message {
+            View::show_info_modal(message, f, area)
         }
 
         if self.config.ui.show_help {
@@ -459,6 +951,143 @@ impl App {
         }
     }
 
+    // Update the items showing a running timer
+    fn refresh_timers(&mut self, items: &mut Vec<ListItem>) {
+        let has_running_timer = match self.view_mode {
+            ViewMode::ViewProjects => self
+                .projects
+                .iter()
+                .any(|p| p.tasks.iter().any(Task::is_timer_running)),
+            ViewMode::ViewTasks => Project::get_current(self)
+                .tasks
+                .iter()
+                .any(Task::is_timer_running),
+            _ => false,
+        };
+
+        if !has_running_timer {
+            return;
+        }
+
+        if self.view_mode == ViewMode::ViewProjects {
+            Project::load_items(self, items)
+        } else {
+            Task::load_items(self, items)
+        }
+    }
+
+    // Move the focus mode to the next phase once the countdown is over
+    fn refresh_focus(&mut self, items: &mut Vec<ListItem>) {
+        let Some(focus) = &self.focus else {
+            return;
+        };
+
+        if !focus.is_over(&self.config.focus) {
+            return;
+        }
+
+        // Terminal bell
+        print!("\x07");
+        stdout().flush().unwrap();
+
+        if focus.phase == FocusPhase::Work {
+            let started_at = focus.started_at;
+            Task::log_focus_session(self, items, started_at, Util::get_now());
+            self.focus = Some(Focus::new(FocusPhase::Break));
+        } else {
+            self.focus = Some(Focus::new(FocusPhase::Idle));
+        }
+    }
+
+    // Key of the action of the mouse event: scrolling moves through the lists,
+    // a click selects an item and a double click opens it (or selects the modal option)
+    fn get_mouse_key(&mut self, mouse: MouseEvent, items: &[ListItem]) -> Option<KeyCode> {
+        let is_list_view = matches!(
+            self.view_mode,
+            ViewMode::ViewProjects | ViewMode::ViewTasks | ViewMode::ViewAgenda
+        );
+        let is_modal_list_view = matches!(
+            self.view_mode,
+            ViewMode::ChangeStatusTask | ViewMode::ChangePriorityTask
+        );
+
+        if !is_list_view && !is_modal_list_view {
+            return None;
+        }
+
+        if is_list_view && items.is_empty() {
+            return None;
+        }
+
+        match mouse.kind {
+            MouseEventKind::ScrollDown => return Some(KeyCode::Down),
+            MouseEventKind::ScrollUp => return Some(KeyCode::Up),
+            MouseEventKind::Down(MouseButton::Left) => {}
+            _ => return None,
+        }
+
+        if is_modal_list_view {
+            let options = if self.view_mode == ViewMode::ChangeStatusTask {
+                TASK_STATUSES.len()
+            } else {
+                TASK_PRIORITIES.len()
+            };
+
+            let index = self.get_clicked_index(self.modal_area, mouse, options)?;
+            self.use_state().select(Some(index));
+
+            return Some(KeyCode::Enter);
+        }
+
+        let index = self.get_clicked_index(self.list_area, mouse, items.len())?;
+        let is_double_click = self
+            .last_click
+            .is_some_and(|(at, i)| i == index && at.elapsed() < DOUBLE_CLICK_TIME);
+
+        self.use_state().select(Some(index));
+
+        if is_double_click {
+            self.last_click = None;
+            return Some(KeyCode::Enter);
+        }
+
+        self.last_click = Some((Instant::now(), index));
+        None
+    }
+
+    // Index of the clicked item of the bordered list, one line per item
+    fn get_clicked_index(&mut self, area: Rect, mouse: MouseEvent, len: usize) -> Option<usize> {
+        let inner_area = area.inner(Margin::new(1, 1));
+
+        if !inner_area.contains(Position::new(mouse.column, mouse.row)) {
+            return None;
+        }
+
+        let index = (mouse.row - inner_area.y) as usize + self.use_state().offset();
+
+        (index < len).then_some(index)
+    }
+
+    // Back to the list the task was changed from
+    fn close_task_modal(&mut self, items: &mut Vec<ListItem>) {
+        if self.is_agenda_open {
+            Agenda::load_items(self, items);
+            App::change_view(self, ViewMode::ViewAgenda)
+        } else {
+            App::change_view(self, ViewMode::ViewTasks)
+        }
+    }
+
+    // Export as markdown to the clipboard command or to the file set in the config
+    fn export(&mut self, projects: &[Project]) {
+        let content = Markdown::export(projects);
+
+        self.info_message = Some(match Interop::send(&self.config.export, &content) {
+            Ok(message) => message,
+            Err(e) => format!("Export failed: {e}"),
+        });
+    }
+
     fn next(&mut self, items: &Vec<ListItem>) -> () {
         let i = match self.use_state().selected() {
             Some(i) => {
@@ -495,16 +1124,28 @@ impl App {
             ViewMode::RenameProject => return &mut self.selected_project_index,
             ViewMode::AddProject => return &mut self.selected_project_index,
             ViewMode::DeleteProject => return &mut self.selected_project_index,
+            ViewMode::ViewStatistics => &mut self.selected_project_index,
+            ViewMode::ViewAgenda => &mut self.selected_agenda_index,
+            ViewMode::ViewCalendar => &mut self.selected_project_index,
 
             ViewMode::ViewTasks => return &mut self.selected_task_index,
+            ViewMode::RenameTask if self.is_agenda_open => &mut self.selected_agenda_index,
             ViewMode::RenameTask => return &mut self.selected_task_index,
             ViewMode::ChangeStatusTask => return &mut self.selected_status_task_index,
             ViewMode::ChangePriorityTask => return &mut self.selected_priority_task_index,
+            ViewMode::ChangeDueDateTask => &mut self.selected_task_index,
+            ViewMode::ChangeRecurrenceTask => &mut self.selected_task_index,
+            ViewMode::ChangeBlockersTask => &mut self.selected_blocker_task_index,
+            ViewMode::ViewTaskDependencies => &mut self.selected_task_index,
+            ViewMode::MoveTasks => &mut self.selected_move_project_index,
+            ViewMode::ArchiveTasks => &mut self.selected_task_index,
             ViewMode::AddTask => return &mut self.selected_task_index,
             ViewMode::DeleteTask => return &mut self.selected_task_index,
+            ViewMode::ViewTaskHistory => &mut self.selected_task_index,
+            ViewMode::FocusTask => &mut self.selected_task_index,
 
             ViewMode::InfoMigration => return &mut self.selected_project_index,
-        };
+        }
     }
 
     fn change_view(&mut self, mode: ViewMode) {
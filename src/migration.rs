@@ -1,71 +1,256 @@
-use serde_json::{
-    json, to_string, Map,
-    Value::{self},
-};
+use std::error::Error;
 
-//                              sha of 0.1.0     0.2.0
-pub static JSON_VERSIONS: [&str; 2] = ["6ad96", "911fc"];
+use serde_json::{json, to_value, Value};
+
+use crate::util::Util;
+
+//                              sha of 0.1.0     0.2.0    0.3.0
+pub static JSON_VERSIONS: [&str; 3] = ["6ad96", "911fc", "b7c3e"];
+
+// A migration receives the list of projects of the previous version
+// and returns the same list updated to its version
+type MigrationFn = fn(Value) -> Result<Value, Box<dyn Error>>;
+
+// Ordered registry: each migration upgrades the json to the version next to it,
+// starting from the previous one in `JSON_VERSIONS`
+static MIGRATIONS: [(&str, MigrationFn); 2] = [
+    ("911fc", Migration::add_priority),
+    ("b7c3e", Migration::add_timestamps),
+];
 
 pub struct Migration;
 
 impl Migration {
-    pub fn get_migrations(version: &str, original_json: Vec<Value>) -> Vec<(&str, String)> {
-        // Mapper between json version and the relative migration
-        let mapper: Vec<(&str, String)> = vec![
-            ("6ad96", "".to_string()),
-            ("911fc", Migration::add_priority(original_json)),
-        ];
-
-        // The start index where the migration are picked
-        let start_index = mapper
-            .clone()
-            .into_iter()
-            .position(|(key, _val)| key == version);
-
-        if start_index.is_none() {
-            return vec![];
-        }
+    // Apply in order all the migrations after the given version, each one on the output
+    // of the previous one. Return the reached version with the migrated json
+    pub fn migrate(version: &str, json: Value) -> Result<(&'static str, Value), Box<dyn Error>> {
+        Migration::run(&JSON_VERSIONS, &MIGRATIONS, version, json)
+    }
+
+    fn run(
+        versions: &[&'static str],
+        migrations: &[(&str, MigrationFn)],
+        version: &str,
+        json: Value,
+    ) -> Result<(&'static str, Value), Box<dyn Error>> {
+        let Some(start_index) = versions.iter().position(|v| *v == version) else {
+            return Err(format!("Unknown json version \"{version}\"").into());
+        };
 
-        let all_migrations: Vec<(&str, String)> = mapper.into_iter().collect();
+        let mut new_json = json;
 
-        // Slice for pick only the useful migration
-        return all_migrations[(start_index.unwrap() + 1)..].to_vec();
+        // Migrations are looked up by version, so a version without a migration is an error
+        // instead of being silently skipped
+        for next_version in &versions[(start_index + 1)..] {
+            let Some((_, migration)) = migrations.iter().find(|(v, _)| v == next_version) else {
+                return Err(
+                    format!("Missing migration for json version \"{next_version}\"").into(),
+                );
+            };
+
+            new_json = migration(new_json)?;
+        }
+
+        Ok((versions.last().unwrap(), new_json))
     }
 
     // Migrations
-    fn add_priority(original_json: Vec<Value>) -> String {
-        let mut internal_json = original_json.clone();
-
-        let new_json: Vec<Map<String, Value>> = internal_json
-            .iter_mut()
-            .map(|p| {
-                // Get all tasks from each project and convert into "Map" type from serde
-                // in order to do some operations with the json
-                // Vec = Array ; Map = Object
-                let mut tasks = serde_json::from_value::<Vec<Map<String, Value>>>(
-                    p.get("tasks").unwrap().clone(),
-                )
-                .unwrap();
-
-                // Add to each task a new key value (i.e. {priority: 0})
-                tasks.iter_mut().for_each(|t| {
-                    // Entry and or_insert methods are used for add a new key
-                    // cf. https://docs.rs/serde_json/latest/serde_json/map/enum.Entry.html#method.or_insert
-                    t.entry("priority").or_insert(json!(0));
-                });
-
-                // Convert "p" into the "Map" type from serde in order to do some operations with the json
-                let mut project = serde_json::from_value::<Map<String, Value>>(p.clone()).unwrap();
-
-                // Replace the "tasks" key with the new one
-                // Insert method is used for replace a new with a new value
-                // cf. https://docs.rs/serde_json/latest/serde_json/map/struct.Map.html#method.insert
-                project.insert("tasks".to_string(), json!(tasks)).unwrap();
-
-                return project;
-            })
-            .collect();
-
-        return to_string(&new_json).unwrap();
+    fn add_priority(json: Value) -> Result<Value, Box<dyn Error>> {
+        let mut new_json = json;
+
+        let projects = new_json
+            .as_array_mut()
+            .ok_or("The list of projects is not an array")?;
+
+        for project in projects.iter_mut() {
+            // Vec = Array ; Map = Object
+            let tasks = project
+                .get_mut("tasks")
+                .and_then(Value::as_array_mut)
+                .ok_or("A project has not the list of tasks")?;
+
+            // Add to each task a new key value (i.e. {priority: 0})
+            for task in tasks.iter_mut() {
+                let task = task.as_object_mut().ok_or("A task is not an object")?;
+
+                // Entry and or_insert methods are used for add a new key
+                // cf. https://docs.rs/serde_json/latest/serde_json/map/enum.Entry.html#method.or_insert
+                task.entry("priority").or_insert(json!(0));
+            }
+        }
+
+        Ok(new_json)
+    }
+
+    fn add_timestamps(json: Value) -> Result<Value, Box<dyn Error>> {
+        let mut new_json = json;
+
+        // The real dates are unknown, the tasks are considered created now. The completion
+        // date is left empty, a done task was not completed at the time of the migration
+        let now = to_value(Util::get_now())?;
+
+        let projects = new_json
+            .as_array_mut()
+            .ok_or("The list of projects is not an array")?;
+
+        for project in projects.iter_mut() {
+            let tasks = project
+                .get_mut("tasks")
+                .and_then(Value::as_array_mut)
+                .ok_or("A project has not the list of tasks")?;
+
+            for task in tasks.iter_mut() {
+                let task = task.as_object_mut().ok_or("A task is not an object")?;
+
+                // A null value is missing too (i.e. a task read without the fields and saved again)
+                for key in ["created_at", "updated_at"] {
+                    if task.get(key).is_none_or(Value::is_null) {
+                        task.insert(key.to_string(), now.clone());
+                    }
+                }
+
+                task.entry("completed_at").or_insert(Value::Null);
+            }
+        }
+
+        Ok(new_json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Same board saved with each json version
+    fn get_fixture(version: &str) -> Value {
+        let raw = match version {
+            "6ad96" => include_str!("../tests/fixtures/migration/6ad96.json"),
+            "911fc" => include_str!("../tests/fixtures/migration/911fc.json"),
+            "b7c3e" => include_str!("../tests/fixtures/migration/b7c3e.json"),
+            _ => panic!("Missing fixture for json version \"{version}\""),
+        };
+
+        serde_json::from_str(raw).unwrap()
+    }
+
+    // The backfilled timestamps are the time of the migration,
+    // they are replaced by the one of the fixtures to compare them
+    fn set_timestamps(json: &mut Value, timestamp: &str) {
+        for project in json.as_array_mut().unwrap() {
+            for task in project["tasks"].as_array_mut().unwrap() {
+                for key in ["created_at", "updated_at", "completed_at"] {
+                    if task.get(key).is_some_and(|v| !v.is_null()) {
+                        task[key] = json!(timestamp);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn registry_covers_every_version() {
+        let registry_versions: Vec<&str> = MIGRATIONS.iter().map(|(v, _)| *v).collect();
+
+        assert_eq!(registry_versions, JSON_VERSIONS[1..]);
+    }
+
+    #[test]
+    fn migrate_each_version_to_last() {
+        let last_version = JSON_VERSIONS.last().unwrap();
+
+        for version in JSON_VERSIONS {
+            let (new_version, mut new_json) =
+                Migration::migrate(version, get_fixture(version)).unwrap();
+            set_timestamps(&mut new_json, "2024-01-01T00:00:00Z");
+
+            assert_eq!(new_version, *last_version);
+            assert_eq!(
+                new_json,
+                get_fixture(last_version),
+                "from version {version}"
+            );
+        }
+    }
+
+    #[test]
+    fn add_priority_keeps_existing_priority() {
+        let json =
+            json!([{ "title": "P", "tasks": [{ "title": "T", "status": "Done", "priority": 2 }] }]);
+
+        assert_eq!(Migration::add_priority(json.clone()).unwrap(), json);
+    }
+
+    #[test]
+    fn add_priority_rejects_invalid_json() {
+        assert!(Migration::add_priority(json!({ "title": "P" })).is_err());
+        assert!(Migration::add_priority(json!([{ "title": "P" }])).is_err());
+    }
+
+    #[test]
+    fn add_timestamps_leaves_tasks_not_completed() {
+        let json = json!([{ "title": "P", "tasks": [
+            { "title": "A", "status": "Done", "priority": 0 },
+            { "title": "B", "status": "UpNext", "priority": 1 }
+        ] }]);
+
+        let new_json = Migration::add_timestamps(json).unwrap();
+        let tasks = &new_json[0]["tasks"];
+
+        assert!(tasks[0]["created_at"].is_string());
+        assert_eq!(tasks[0]["updated_at"], tasks[0]["created_at"]);
+        assert!(tasks[0]["completed_at"].is_null());
+        assert!(tasks[1]["updated_at"].is_string());
+        assert!(tasks[1]["completed_at"].is_null());
+    }
+
+    #[test]
+    fn add_timestamps_keeps_existing_timestamps() {
+        let json = json!([{ "title": "P", "tasks": [{
+            "title": "T",
+            "status": "Done",
+            "priority": 0,
+            "created_at": "2020-01-01T00:00:00Z",
+            "updated_at": "2020-01-02T00:00:00Z",
+            "completed_at": "2020-01-02T00:00:00Z"
+        }] }]);
+
+        assert_eq!(Migration::add_timestamps(json.clone()).unwrap(), json);
+    }
+
+    #[test]
+    fn migrate_unknown_version_fails() {
+        assert!(Migration::migrate("00000", json!([])).is_err());
+    }
+
+    #[test]
+    fn migrations_are_chained() {
+        fn add_a(mut json: Value) -> Result<Value, Box<dyn Error>> {
+            json["a"] = json!(1);
+            Ok(json)
+        }
+
+        // Works only on the output of `add_a`
+        fn double_a(mut json: Value) -> Result<Value, Box<dyn Error>> {
+            let a = json["a"].as_i64().ok_or("Missing a")?;
+            json["a"] = json!(a * 2);
+            Ok(json)
+        }
+
+        let versions = ["v1", "v2", "v3"];
+        let migrations: [(&str, MigrationFn); 2] = [("v2", add_a), ("v3", double_a)];
+
+        let (version, json) = Migration::run(&versions, &migrations, "v1", json!({})).unwrap();
+        assert_eq!(version, "v3");
+        assert_eq!(json, json!({ "a": 2 }));
+
+        let (_, json) = Migration::run(&versions, &migrations, "v2", json!({ "a": 5 })).unwrap();
+        assert_eq!(json, json!({ "a": 10 }));
+    }
+
+    #[test]
+    fn migrate_fails_without_registered_migration() {
+        let versions = ["v1", "v2"];
+
+        assert!(Migration::run(&versions, &[], "v1", json!([])).is_err());
     }
 }
@@ -1,3 +1,4 @@
+use chrono::TimeDelta;
 use ratatui::{
     style::{Color, Style},
     text::{Line, Span},
@@ -6,19 +7,22 @@ use ratatui::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    json::Json,
     task::{Task, TASK_STATUS_DONE},
+    util::Util,
     App,
 };
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Project {
+    // Row id used by the storage backends with indexed writes (i.e. SQLite)
+    #[serde(skip)]
+    pub id: Option<i64>,
     pub title: String,
     pub tasks: Vec<Task>,
 }
 
 impl Project {
-    fn get_indicator_done_tasks_color(percentage: usize) -> ratatui::prelude::Color {
+    pub fn get_indicator_done_tasks_color(percentage: usize) -> ratatui::prelude::Color {
         match percentage {
             p if p == 0 => return Color::DarkGray,
             p if p >= 25 && p <= 50 => return Color::LightMagenta,
@@ -28,37 +32,68 @@ impl Project {
         }
     }
 
+    // Done tasks, all the tasks and the percentage of the done ones
+    pub fn get_progress(project: &Project) -> (usize, usize, usize) {
+        let tasks = &project.tasks;
+
+        let done_tasks: Vec<Task> = tasks
+            .clone()
+            .into_iter()
+            .filter(|t| t.status == TASK_STATUS_DONE)
+            .collect();
+
+        let percentage = if tasks.len() == 0 {
+            0
+        } else {
+            (done_tasks.len() * 100) / tasks.len()
+        };
+
+        (done_tasks.len(), tasks.len(), percentage)
+    }
+
+    // Time tracked on all the tasks of the project
+    pub fn get_tracked_time(project: &Project) -> TimeDelta {
+        project.tasks.iter().map(Task::get_tracked_time).sum()
+    }
+
     pub fn load_items(app: &mut App, items: &mut Vec<ListItem>) {
         items.clear();
 
         for project in app.projects.iter() {
-            let tasks = &project.tasks;
-
-            let done_tasks: Vec<Task> = tasks
-                .clone()
-                .into_iter()
-                .filter(|t| t.status == TASK_STATUS_DONE)
-                .collect();
-
-            let percentage = if tasks.len() == 0 {
-                0
-            } else {
-                (done_tasks.len() * 100) / tasks.len()
-            };
-
-            let lines = vec![Line::from(vec![
-                Span::raw(format!("[{}/{}] ", done_tasks.len(), tasks.len(),)).style(
+            let (done, total, percentage) = Project::get_progress(project);
+
+            let mut repr = vec![
+                Span::raw(format!("[{}/{}] ", done, total,)).style(
                     Style::default().fg(Project::get_indicator_done_tasks_color(percentage)),
                 ),
                 Span::raw(project.title.clone()),
-            ])];
+            ];
+
+            if project.tasks.iter().any(|t| !t.time_entries.is_empty()) {
+                let color = if project.tasks.iter().any(Task::is_timer_running) {
+                    Color::Yellow
+                } else {
+                    Color::DarkGray
+                };
+
+                repr.push(Span::styled(
+                    format!(
+                        " {}",
+                        Util::get_clock_repr(Project::get_tracked_time(project))
+                    ),
+                    Style::new().fg(color),
+                ));
+            }
+
+            let lines = vec![Line::from(repr)];
 
             items.push(ListItem::from(lines))
         }
     }
 
-    pub fn reload(app: &mut App, items: &mut Vec<ListItem>) {
-        app.projects = Json::read();
+    // Show the saved board
+    pub fn reload(app: &mut App, items: &mut Vec<ListItem>, internal_projects: Vec<Project>) {
+        app.projects = app.store.get_saved_projects(internal_projects);
         Project::load_items(app, items)
     }
 
@@ -72,6 +107,7 @@ impl Project {
         }
 
         let new_project = Project {
+            id: None,
             title: value.to_string(),
             tasks: vec![],
         };
@@ -79,8 +115,9 @@ impl Project {
         let mut internal_projects = app.projects.clone();
         internal_projects.push(new_project);
 
-        Json::write(internal_projects);
-        Project::reload(app, items)
+        app.store
+            .save_project(&internal_projects, internal_projects.len() - 1);
+        Project::reload(app, items, internal_projects)
     }
 
     pub fn rename(app: &mut App, items: &mut Vec<ListItem>, value: &str) {
@@ -88,16 +125,21 @@ impl Project {
 
         internal_projects[app.selected_project_index.selected().unwrap()].title = value.to_string();
 
-        Json::write(internal_projects);
-        Project::reload(app, items)
+        app.store.save_project(
+            &internal_projects,
+            app.selected_project_index.selected().unwrap(),
+        );
+        Project::reload(app, items, internal_projects)
     }
 
     pub fn delete(app: &mut App, items: &mut Vec<ListItem>) {
         let mut internal_projects = app.projects.clone();
 
-        internal_projects.remove(app.selected_project_index.selected().unwrap());
+        let removed_project =
+            internal_projects.remove(app.selected_project_index.selected().unwrap());
 
-        Json::write(internal_projects);
-        Project::reload(app, items)
+        app.store
+            .delete_project(&internal_projects, &removed_project);
+        Project::reload(app, items, internal_projects)
     }
 }
@@ -0,0 +1,177 @@
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+// Rule to create the next occurrence of a task when it's done.
+// Written and shown as text: "daily", "weekly mon,thu", "monthly 15", "every 3 days"
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "every", rename_all = "snake_case")]
+pub enum Recurrence {
+    Day,
+    Week { days: Vec<Weekday> },
+    Month { day: u32 },
+    Days { count: u64 },
+}
+
+impl Recurrence {
+    // The missing days of the weekly and monthly rules are taken from the date
+    pub fn parse(value: &str, date: NaiveDate) -> Result<Recurrence, String> {
+        let words: Vec<String> = value.split_whitespace().map(|w| w.to_lowercase()).collect();
+        let words: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+
+        match words.as_slice() {
+            ["daily"] => Ok(Recurrence::Day),
+            ["weekly"] => Ok(Recurrence::Week {
+                days: vec![date.weekday()],
+            }),
+            ["weekly", days] => {
+                let mut weekdays: Vec<Weekday> = vec![];
+
+                for day in days.split(',').filter(|d| !d.is_empty()) {
+                    match day.parse::<Weekday>() {
+                        Ok(weekday) if !weekdays.contains(&weekday) => weekdays.push(weekday),
+                        Ok(_) => {}
+                        Err(_) => return Err(format!("Unknown day \"{day}\"")),
+                    }
+                }
+
+                // Same order of the week
+                weekdays.sort_by_key(|d| d.num_days_from_monday());
+
+                Ok(Recurrence::Week { days: weekdays })
+            }
+            ["monthly"] => Ok(Recurrence::Month { day: date.day() }),
+            ["monthly", day] => match day.parse::<u32>() {
+                Ok(day) if (1..=31).contains(&day) => Ok(Recurrence::Month { day }),
+                _ => Err(format!("Invalid day of the month \"{day}\"")),
+            },
+            ["every", count, "day" | "days"] => match count.parse::<u64>() {
+                Ok(count) if count > 0 => Ok(Recurrence::Days { count }),
+                _ => Err(format!("Invalid number of days \"{count}\"")),
+            },
+            _ => Err(format!(
+                "Invalid recurrence \"{value}\", expected one of: daily, weekly [mon,thu,...], monthly [day], every <n> days"
+            )),
+        }
+    }
+
+    pub fn get_repr(&self) -> String {
+        match self {
+            Recurrence::Day => "daily".to_string(),
+            Recurrence::Week { days } => format!(
+                "weekly {}",
+                days.iter()
+                    .map(|d| d.to_string().to_lowercase())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            Recurrence::Month { day } => format!("monthly {day}"),
+            Recurrence::Days { count } => format!("every {count} days"),
+        }
+    }
+
+    // First date of the rule after the given one
+    pub fn get_next(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Recurrence::Day => date + Days::new(1),
+            Recurrence::Week { days } if days.is_empty() => date + Days::new(7),
+            Recurrence::Week { days } => (1..=7)
+                .map(|n| date + Days::new(n))
+                .find(|d| days.contains(&d.weekday()))
+                .unwrap(),
+            Recurrence::Month { day } => {
+                let this_month = Recurrence::get_day_of_month(date, *day);
+
+                if this_month > date {
+                    this_month
+                } else {
+                    Recurrence::get_day_of_month(date + Months::new(1), *day)
+                }
+            }
+            Recurrence::Days { count } => date + Days::new(*count),
+        }
+    }
+
+    // The next date of the rule from the due date, the occurrences already past are skipped
+    pub fn get_next_due(&self, due_date: Option<NaiveDate>, today: NaiveDate) -> NaiveDate {
+        let mut next = self.get_next(due_date.unwrap_or(today));
+
+        while next < today {
+            next = self.get_next(next);
+        }
+
+        next
+    }
+
+    // The day in the month of the date, or its last day (i.e. 31 => 30 of April)
+    fn get_day_of_month(date: NaiveDate, day: u32) -> NaiveDate {
+        if let Some(date) = date.with_day(day) {
+            return date;
+        }
+
+        (28..day).rev().find_map(|d| date.with_day(d)).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_helpers::date;
+
+    #[test]
+    fn parse_and_repr() {
+        let today = date("2026-10-15");
+
+        for (value, repr) in [
+            ("daily", "daily"),
+            ("Weekly", "weekly thu"),
+            ("weekly fri,mon,friday", "weekly mon,fri"),
+            ("monthly", "monthly 15"),
+            ("monthly 31", "monthly 31"),
+            ("every 3 days", "every 3 days"),
+        ] {
+            let recurrence = Recurrence::parse(value, today).unwrap();
+            assert_eq!(recurrence.get_repr(), repr);
+            assert_eq!(Recurrence::parse(repr, today).unwrap(), recurrence);
+        }
+
+        for value in ["", "yearly", "weekly funday", "monthly 32", "every 0 days"] {
+            assert!(Recurrence::parse(value, today).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn next_dates() {
+        let weekly = Recurrence::Week {
+            days: vec![Weekday::Mon, Weekday::Thu],
+        };
+        // Thursday
+        assert_eq!(weekly.get_next(date("2026-10-15")), date("2026-10-19"));
+        assert_eq!(weekly.get_next(date("2026-10-19")), date("2026-10-22"));
+
+        let monthly = Recurrence::Month { day: 31 };
+        assert_eq!(monthly.get_next(date("2026-10-31")), date("2026-11-30"));
+        assert_eq!(monthly.get_next(date("2026-11-30")), date("2026-12-31"));
+        assert_eq!(monthly.get_next(date("2026-02-10")), date("2026-02-28"));
+
+        let every = Recurrence::Days { count: 3 };
+        assert_eq!(every.get_next(date("2026-10-30")), date("2026-11-02"));
+    }
+
+    #[test]
+    fn next_due_skips_past_occurrences() {
+        let today = date("2026-10-15");
+
+        assert_eq!(
+            Recurrence::Day.get_next_due(Some(date("2026-10-10")), today),
+            today
+        );
+        assert_eq!(
+            Recurrence::Day.get_next_due(None, today),
+            date("2026-10-16")
+        );
+        assert_eq!(
+            Recurrence::Days { count: 7 }.get_next_due(Some(date("2026-10-20")), today),
+            date("2026-10-27")
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::Local;
+use ratatui::style::Color;
+
+use crate::{
+    project::Project,
+    task::{Task, TASK_STATUSES_SORT_ORDER, TASK_STATUS_DONE},
+    util::Util,
+};
+
+// Read-only page of the board, without external resources so it can be shared as a single file
+pub struct Report;
+
+static STYLE: &str = "
+body { background: #1e1e1e; color: #e0e0e0; font-family: monospace; margin: 2em auto; max-width: 60em; }
+h1 { font-size: 1.4em; }
+h2 { font-size: 1.2em; margin-bottom: 0.3em; }
+h3 { font-size: 1em; margin: 0.8em 0 0.3em; }
+section { border: 1px solid #444; border-radius: 4px; margin: 1em 0; padding: 0.5em 1em 1em; }
+.bar { background: #333; border-radius: 2px; height: 0.8em; }
+.bar div { border-radius: 2px; height: 100%; }
+.progress { color: #999; font-size: 0.9em; margin: 0.3em 0; }
+ul { list-style: none; margin: 0; padding-left: 1em; }
+.done { text-decoration: line-through; }
+.priority { color: #ff5555; }
+.tag { color: #777; }
+footer { color: #777; font-size: 0.8em; }
+";
+
+impl Report {
+    // Css colour of the colours used in the app
+    fn get_css_color(color: Color) -> &'static str {
+        match color {
+            Color::LightGreen => "#55ff55",
+            Color::Yellow => "#cdcd00",
+            Color::LightYellow => "#ffff55",
+            Color::LightMagenta => "#ff55ff",
+            Color::DarkGray => "#7f7f7f",
+            Color::Gray => "#bfbfbf",
+            _ => "#ffffff",
+        }
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    fn get_task_html(task: &Task) -> String {
+        let mut html = String::from("<li>");
+
+        if task.priority != 0 {
+            html += &format!(
+                "<span class=\"priority\">[{}]</span> ",
+                Util::get_priority_indicator(task.priority)
+            );
+        }
+
+        let class = if task.status == TASK_STATUS_DONE {
+            "done"
+        } else {
+            ""
+        };
+
+        html += &format!(
+            "<span class=\"{class}\">{}</span>",
+            Report::escape(&task.title)
+        );
+
+        for tag in task.tags.iter() {
+            html += &format!(" <span class=\"tag\">#{}</span>", Report::escape(tag));
+        }
+
+        html + "</li>"
+    }
+
+    fn get_project_html(project: &Project) -> String {
+        let (done, total, percentage) = Project::get_progress(project);
+        let bar_color = Report::get_css_color(Project::get_indicator_done_tasks_color(percentage));
+
+        let mut html = format!(
+            "<section>\n<h2>{}</h2>\n\
+            <div class=\"bar\"><div style=\"width: {percentage}%; background: {bar_color}\"></div></div>\n\
+            <div class=\"progress\">{done}/{total} done ({percentage}%)</div>\n",
+            Report::escape(&project.title)
+        );
+
+        let mut tasks = project.tasks.clone();
+        Task::sort(&mut tasks);
+
+        for status in TASK_STATUSES_SORT_ORDER {
+            let status_tasks: Vec<&Task> = tasks.iter().filter(|t| t.status == status).collect();
+
+            if status_tasks.is_empty() {
+                continue;
+            }
+
+            let color = Report::get_css_color(Task::get_status_color(&status.to_string()));
+
+            html += &format!(
+                "<h3 style=\"color: {color}\">{status} ({})</h3>\n<ul>\n",
+                status_tasks.len()
+            );
+
+            for task in status_tasks {
+                html += &(Report::get_task_html(task) + "\n");
+            }
+
+            html += "</ul>\n";
+        }
+
+        html + "</section>\n"
+    }
+
+    pub fn html(projects: &[Project]) -> String {
+        let generated_at = Local::now().format("%Y-%m-%d %H:%M").to_string();
+
+        let body: String = projects.iter().map(Report::get_project_html).collect();
+
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+            <title>{name} report</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+            <h1>{name} report</h1>\n{body}\
+            <footer>Generated on {generated_at} by {name} {version}</footer>\n</body>\n</html>\n",
+            name = env!("CARGO_PKG_NAME"),
+            version = env!("CARGO_PKG_VERSION"),
+        )
+    }
+}
@@ -0,0 +1,318 @@
+use std::{error::Error, fs, path::PathBuf};
+
+use rusqlite::{params, Connection};
+use serde_json::{from_str, from_value, to_string, to_value, Value};
+
+use crate::{json::Json, migration::Migration, project::Project, store::Store, task::Task};
+
+pub struct Sqlite {
+    connection: Connection,
+}
+
+static DB_FILE_NAME: &str = env!("CARGO_PKG_NAME");
+static SCHEMA_VERSION: i64 = 2;
+// Json version of the tasks saved with each schema version (the first one is 1)
+static SCHEMA_JSON_VERSIONS: [&str; 2] = ["911fc", "b7c3e"];
+
+// Status, priority and title are stored in their own columns to be indexed,
+// the whole task is also kept as json so new fields don't need a new column
+static SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS projects (
+        id INTEGER PRIMARY KEY,
+        position INTEGER NOT NULL,
+        title TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS tasks (
+        id INTEGER PRIMARY KEY,
+        project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        title TEXT NOT NULL,
+        status TEXT NOT NULL,
+        priority INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS tasks_project_position ON tasks(project_id, position);
+    CREATE INDEX IF NOT EXISTS tasks_status ON tasks(status);
+";
+
+impl Sqlite {
+    fn get_db_path() -> PathBuf {
+        let mut path = PathBuf::new();
+        path.push(Json::get_dir_path().as_path());
+        path.push(format!("{DB_FILE_NAME}.db"));
+
+        path
+    }
+
+    pub fn open() -> Result<Sqlite, Box<dyn Error>> {
+        fs::create_dir_all(Json::get_dir_path())?;
+
+        let connection = Connection::open(Sqlite::get_db_path())?;
+        connection.pragma_update(None, "foreign_keys", true)?;
+
+        Ok(Sqlite { connection })
+    }
+
+    // The ids already given are kept, so the ones in memory are still valid
+    fn insert_project(&self, project: &Project, position: usize) -> rusqlite::Result<i64> {
+        self.connection.execute(
+            "INSERT INTO projects (id, position, title) VALUES (?1, ?2, ?3)",
+            params![project.id, position, project.title],
+        )?;
+
+        let project_id = self.connection.last_insert_rowid();
+
+        for (position, task) in project.tasks.iter().enumerate() {
+            self.insert_task(project_id, task, Some(position))?;
+        }
+
+        Ok(project_id)
+    }
+
+    // Without a position the task is appended to the project
+    fn insert_task(
+        &self,
+        project_id: i64,
+        task: &Task,
+        position: Option<usize>,
+    ) -> rusqlite::Result<()> {
+        let position = match position {
+            Some(p) => p as i64,
+            None => self.connection.query_row(
+                "SELECT COALESCE(MAX(position) + 1, 0) FROM tasks WHERE project_id = ?1",
+                params![project_id],
+                |row| row.get(0),
+            )?,
+        };
+
+        self.connection.execute(
+            "INSERT INTO tasks (id, project_id, position, title, status, priority, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                task.id,
+                project_id,
+                position,
+                task.title,
+                task.status,
+                task.priority,
+                to_string(task).unwrap()
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    // Keep the stored order aligned with the in memory one
+    fn update_positions(&self, projects: &[Project]) -> rusqlite::Result<()> {
+        for (position, project) in projects.iter().enumerate() {
+            if let Some(id) = project.id {
+                self.connection.execute(
+                    "UPDATE projects SET position = ?1 WHERE id = ?2",
+                    params![position, id],
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Store for Sqlite {
+    fn check(&self) -> Result<bool, Box<dyn Error>> {
+        let version: i64 = self
+            .connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        if version == SCHEMA_VERSION {
+            return Ok(false);
+        }
+
+        let pending_migration = self.get_pending_migration()?;
+
+        self.connection.execute_batch(SCHEMA)?;
+
+        if let Some((_json, new_json)) = &pending_migration {
+            self.save_all(&from_value::<Vec<Project>>(new_json.clone())?);
+        }
+
+        self.connection
+            .pragma_update(None, "user_version", SCHEMA_VERSION)?;
+
+        Ok(pending_migration.is_some())
+    }
+
+    fn get_pending_migration(&self) -> Result<Option<(Value, Value)>, Box<dyn Error>> {
+        let version: i64 = self
+            .connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        // A database saved by a newer basilk can't be migrated back
+        if !(0..=SCHEMA_VERSION).contains(&version) {
+            return Err(format!(
+                "The database schema {version} is newer than this version of basilk"
+            )
+            .into());
+        }
+
+        // A new database has nothing to migrate
+        if version == 0 || version == SCHEMA_VERSION {
+            return Ok(None);
+        }
+
+        let json = to_value(self.load())?;
+
+        if json.as_array().is_some_and(|p| p.is_empty()) {
+            return Ok(None);
+        }
+
+        let json_version = SCHEMA_JSON_VERSIONS[version as usize - 1];
+        let (_version, new_json) = Migration::migrate(json_version, json.clone())?;
+
+        Ok(Some((json, new_json)))
+    }
+
+    fn load(&self) -> Vec<Project> {
+        let mut projects_statement = self
+            .connection
+            .prepare("SELECT id, title FROM projects ORDER BY position")
+            .unwrap();
+        let mut tasks_statement = self
+            .connection
+            .prepare("SELECT id, data FROM tasks WHERE project_id = ?1 ORDER BY position")
+            .unwrap();
+
+        let rows = projects_statement
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })
+            .unwrap();
+
+        rows.map(|row| {
+            let (project_id, title) = row.unwrap();
+
+            let tasks = tasks_statement
+                .query_map(params![project_id], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+                })
+                .unwrap()
+                .map(|row| {
+                    let (task_id, data) = row.unwrap();
+
+                    let mut task = from_str::<Task>(&data).unwrap();
+                    task.id = Some(task_id);
+
+                    task
+                })
+                .collect();
+
+            Project {
+                id: Some(project_id),
+                title,
+                tasks,
+            }
+        })
+        .collect()
+    }
+
+    fn save_all(&self, projects: &[Project]) {
+        let transaction = self.connection.unchecked_transaction().unwrap();
+
+        self.connection
+            .execute_batch("DELETE FROM tasks; DELETE FROM projects;")
+            .unwrap();
+
+        for (position, project) in projects.iter().enumerate() {
+            self.insert_project(project, position).unwrap();
+        }
+
+        transaction.commit().unwrap();
+    }
+
+    fn save_project(&self, projects: &[Project], index: usize) {
+        let project = &projects[index];
+        let transaction = self.connection.unchecked_transaction().unwrap();
+
+        match project.id {
+            Some(id) => {
+                self.connection
+                    .execute(
+                        "UPDATE projects SET title = ?1 WHERE id = ?2",
+                        params![project.title, id],
+                    )
+                    .unwrap();
+            }
+            None => {
+                self.insert_project(project, index).unwrap();
+                self.update_positions(projects).unwrap();
+            }
+        }
+
+        transaction.commit().unwrap();
+    }
+
+    fn delete_project(&self, projects: &[Project], removed: &Project) {
+        let Some(id) = removed.id else {
+            return;
+        };
+
+        let transaction = self.connection.unchecked_transaction().unwrap();
+
+        self.connection
+            .execute("DELETE FROM projects WHERE id = ?1", params![id])
+            .unwrap();
+        self.update_positions(projects).unwrap();
+
+        transaction.commit().unwrap();
+    }
+
+    fn save_task(&self, projects: &[Project], project_index: usize, task_index: usize) {
+        let project = &projects[project_index];
+        let task = &project.tasks[task_index];
+
+        let Some(project_id) = project.id else {
+            return self.save_project(projects, project_index);
+        };
+
+        match task.id {
+            Some(id) => {
+                self.connection
+                    .execute(
+                        "UPDATE tasks SET title = ?1, status = ?2, priority = ?3, data = ?4
+                         WHERE id = ?5",
+                        params![
+                            task.title,
+                            task.status,
+                            task.priority,
+                            to_string(task).unwrap(),
+                            id
+                        ],
+                    )
+                    .unwrap();
+            }
+            None => self.insert_task(project_id, task, None).unwrap(),
+        }
+    }
+
+    // The rows inserted without id are read again to have it
+    fn get_saved_projects(&self, projects: Vec<Project>) -> Vec<Project> {
+        let is_missing_id = projects
+            .iter()
+            .any(|p| p.id.is_none() || p.tasks.iter().any(|t| t.id.is_none()));
+
+        if is_missing_id {
+            return self.load();
+        }
+
+        projects
+    }
+
+    fn delete_task(&self, _projects: &[Project], _project_index: usize, removed: &Task) {
+        if let Some(id) = removed.id {
+            self.connection
+                .execute("DELETE FROM tasks WHERE id = ?1", params![id])
+                .unwrap();
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::cmp::Reverse;
+
+use chrono::{Datelike, Days, TimeDelta};
+
+use crate::{
+    project::Project,
+    task::{TaskChange, TASK_PRIORITIES, TASK_STATUSES, TASK_STATUS_DONE, TASK_STATUS_ON_GOING},
+    util::Util,
+};
+
+// Aggregate metrics of all the projects, shown in the statistics view
+pub struct Statistics {
+    // Tasks of each status, in the order of `TASK_STATUSES`
+    pub status_counts: Vec<(&'static str, u64)>,
+    // Open tasks of each priority, in the order of `TASK_PRIORITIES`
+    pub priority_counts: Vec<(u8, u64)>,
+    // Title, done tasks, all the tasks and percentage (as shown in the projects list)
+    pub projects_progress: Vec<(String, usize, usize, usize)>,
+    // Projects with more open tasks first
+    pub largest_open_projects: Vec<(String, u64)>,
+    // Tasks completed in each of the last weeks (labelled by their monday), oldest first
+    pub weekly_done: Vec<(String, u64)>,
+    // Average time from the creation to the completion of the done tasks
+    pub average_lead_time: Option<TimeDelta>,
+    // Average time from the start (first move to OnGoing) to the completion of the done tasks
+    pub average_cycle_time: Option<TimeDelta>,
+}
+
+// Projects shown in the largest open projects chart
+static LARGEST_OPEN_PROJECTS_LIMIT: usize = 5;
+// Weeks shown in the throughput chart
+static THROUGHPUT_WEEKS: u64 = 6;
+
+impl Statistics {
+    pub fn new(projects: &[Project]) -> Statistics {
+        let tasks = projects.iter().flat_map(|p| p.tasks.iter());
+
+        let status_counts = TASK_STATUSES
+            .into_iter()
+            .map(|status| {
+                let count = tasks.clone().filter(|t| t.status == status).count();
+                (status, count as u64)
+            })
+            .collect();
+
+        let priority_counts = TASK_PRIORITIES
+            .into_iter()
+            .map(|priority| {
+                let count = tasks
+                    .clone()
+                    .filter(|t| t.status != TASK_STATUS_DONE && t.priority == priority)
+                    .count();
+                (priority, count as u64)
+            })
+            .collect();
+
+        let projects_progress = projects
+            .iter()
+            .map(|project| {
+                let (done, total, percentage) = Project::get_progress(project);
+                (project.title.clone(), done, total, percentage)
+            })
+            .collect();
+
+        let mut largest_open_projects: Vec<(String, u64)> = projects
+            .iter()
+            .map(|project| {
+                let (done, total, _) = Project::get_progress(project);
+                (project.title.clone(), (total - done) as u64)
+            })
+            .filter(|(_, open)| *open > 0)
+            .collect();
+
+        // Stable sort, projects with the same open tasks keep the board order
+        largest_open_projects.sort_by_key(|(_, open)| Reverse(*open));
+        largest_open_projects.truncate(LARGEST_OPEN_PROJECTS_LIMIT);
+
+        let today = Util::get_now().date_naive();
+        let this_monday = today - Days::new(today.weekday().num_days_from_monday() as u64);
+
+        let weekly_done = (0..THROUGHPUT_WEEKS)
+            .rev()
+            .map(|weeks_ago| {
+                let monday = this_monday - Days::new(weeks_ago * 7);
+                let count = tasks
+                    .clone()
+                    .filter_map(|t| t.completed_at)
+                    .filter(|c| c.date_naive() >= monday && c.date_naive() < monday + Days::new(7))
+                    .count();
+
+                (monday.format("%d/%m").to_string(), count as u64)
+            })
+            .collect();
+
+        let lead_times: Vec<TimeDelta> = tasks
+            .clone()
+            .filter(|t| t.status == TASK_STATUS_DONE)
+            .filter_map(|t| Some(t.completed_at? - t.created_at?))
+            .collect();
+
+        let cycle_times: Vec<TimeDelta> = tasks
+            .clone()
+            .filter(|t| t.status == TASK_STATUS_DONE)
+            .filter_map(|t| {
+                let started_at = t.history.iter().find_map(|e| match &e.change {
+                    TaskChange::Status { to, .. } if to == TASK_STATUS_ON_GOING => Some(e.at),
+                    _ => None,
+                })?;
+
+                Some(t.completed_at? - started_at)
+            })
+            .collect();
+
+        Statistics {
+            status_counts,
+            priority_counts,
+            projects_progress,
+            largest_open_projects,
+            weekly_done,
+            average_lead_time: Statistics::get_average(&lead_times),
+            average_cycle_time: Statistics::get_average(&cycle_times),
+        }
+    }
+
+    fn get_average(durations: &[TimeDelta]) -> Option<TimeDelta> {
+        if durations.is_empty() {
+            return None;
+        }
+
+        Some(durations.iter().sum::<TimeDelta>() / durations.len() as i32)
+    }
+}
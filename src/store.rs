@@ -0,0 +1,111 @@
+use std::error::Error;
+
+use serde_json::Value;
+
+use crate::{
+    config::{Backend, Storage},
+    directory::Directory,
+    json::Json,
+    project::Project,
+    sqlite::Sqlite,
+    task::Task,
+};
+
+// Every persistence backend implements this trait.
+// The incremental methods receive the whole board (already modified in memory) plus the
+// position of what changed: backends able to write a single record override them,
+// the others just rewrite everything
+pub trait Store {
+    // Prepare the storage and apply the pending migrations.
+    // Return true if some migrations were applied
+    fn check(&self) -> Result<bool, Box<dyn Error>>;
+
+    fn load(&self) -> Vec<Project>;
+
+    fn save_all(&self, projects: &[Project]);
+
+    // The list of projects before and after the pending json migrations, nothing is written.
+    // None when the storage is up to date or has no json migrations (i.e. SQLite)
+    fn get_pending_migration(&self) -> Result<Option<(Value, Value)>, Box<dyn Error>> {
+        Ok(None)
+    }
+
+    fn save_project(&self, projects: &[Project], _index: usize) {
+        self.save_all(projects)
+    }
+
+    fn delete_project(&self, projects: &[Project], _removed: &Project) {
+        self.save_all(projects)
+    }
+
+    fn save_task(&self, projects: &[Project], _project_index: usize, _task_index: usize) {
+        self.save_all(projects)
+    }
+
+    fn delete_task(&self, projects: &[Project], _project_index: usize, _removed: &Task) {
+        self.save_all(projects)
+    }
+
+    // The board once saved, without reading it again unless the storage
+    // has to give an id to the new records
+    fn get_saved_projects(&self, projects: Vec<Project>) -> Vec<Project> {
+        projects
+    }
+}
+
+impl Backend {
+    pub fn from_name(name: &str) -> Option<Backend> {
+        match name {
+            "json" => Some(Backend::Json),
+            "sqlite" => Some(Backend::Sqlite),
+            "directory" => Some(Backend::Directory),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        match self {
+            Backend::Json => "json",
+            Backend::Sqlite => "sqlite",
+            Backend::Directory => "directory",
+        }
+    }
+}
+
+impl Storage {
+    pub fn open(&self) -> Result<Box<dyn Store>, Box<dyn Error>> {
+        self.open_backend(&self.backend)
+    }
+
+    pub fn open_backend(&self, backend: &Backend) -> Result<Box<dyn Store>, Box<dyn Error>> {
+        match backend {
+            Backend::Json => Ok(Box::new(Json::new(self.pretty_json))),
+            Backend::Sqlite => Ok(Box::new(Sqlite::open()?)),
+            Backend::Directory => Ok(Box::new(Directory::open(self.format)?)),
+        }
+    }
+
+    // One-shot copy of the whole board from a backend to another one.
+    // Return the number of converted projects
+    pub fn convert(
+        &self,
+        from: &Backend,
+        to: &Backend,
+        force: bool,
+    ) -> Result<usize, Box<dyn Error>> {
+        let source = self.open_backend(from)?;
+        source.check()?;
+
+        let target = self.open_backend(to)?;
+        target.check()?;
+
+        if !force && !target.load().is_empty() {
+            return Err("The target storage is not empty, use --force to overwrite it".into());
+        }
+
+        let projects = source.load();
+        target.save_all(&projects);
+
+        Ok(projects.len())
+    }
+}
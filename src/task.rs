@@ -1,3 +1,6 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{DateTime, Local, NaiveDate, TimeDelta, Utc};
 use ratatui::{
     style::{Color, Modifier, Style},
     text::{Line, Span},
@@ -5,13 +8,175 @@ use ratatui::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{json::Json, util::Util, App};
+use crate::{interop::Interop, project::Project, recurrence::Recurrence, util::Util, App};
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct Task {
+    // Row id used by the storage backends with indexed writes (i.e. SQLite)
+    #[serde(skip)]
+    pub id: Option<i64>,
+    // Stable id referenced by the dependencies and the exports, set the first time it's needed
+    #[serde(default)]
+    pub uid: Option<String>,
     pub title: String,
     pub status: String,
     pub priority: u8,
+    // Free text notes, one per line (i.e. the annotations of Taskwarrior)
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    // Set while the task is Done
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+    // Activity log, oldest first
+    #[serde(default)]
+    pub history: Vec<TaskEvent>,
+    // Tracked intervals, the last one is running when it has no end
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+    // Completed sessions of the focus mode
+    #[serde(default)]
+    pub focus_sessions: Vec<TimeEntry>,
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+    // Once done, the next occurrence is created in UpNext and the rule moves to it
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    // Uids of the tasks (of any project) to be done before this one
+    #[serde(default)]
+    pub blocked_by: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TimeEntry {
+    pub start: DateTime<Utc>,
+    #[serde(default)]
+    pub end: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TaskEvent {
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub change: TaskChange,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum TaskChange {
+    Created,
+    Renamed {
+        from: String,
+        to: String,
+    },
+    Status {
+        from: String,
+        to: String,
+    },
+    Priority {
+        from: u8,
+        to: u8,
+    },
+    Recurred {
+        due_date: NaiveDate,
+    },
+    Moved {
+        from: String,
+        to: String,
+    },
+    DueDate {
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    },
+    Recurrence {
+        from: Option<Recurrence>,
+        to: Option<Recurrence>,
+    },
+    Timer {
+        running: bool,
+    },
+    Focused {
+        minutes: i64,
+    },
+    Blocked {
+        by: String,
+    },
+    Unblocked {
+        by: String,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ChecklistItem {
+    pub title: String,
+    pub done: bool,
+}
+
+impl TaskChange {
+    pub fn get_repr(&self) -> Vec<Span<'static>> {
+        let get_due_date_repr = |due_date: &Option<NaiveDate>| match due_date {
+            Some(due_date) => due_date.to_string(),
+            None => "none".to_string(),
+        };
+        let get_recurrence_repr = |recurrence: &Option<Recurrence>| match recurrence {
+            Some(recurrence) => recurrence.get_repr(),
+            None => "none".to_string(),
+        };
+        let get_priority_repr = |priority: u8| match Util::get_priority_indicator(priority) {
+            indicator if indicator.is_empty() => "none".to_string(),
+            indicator => indicator,
+        };
+
+        match self {
+            TaskChange::Created => vec![Span::raw("Created")],
+            TaskChange::Renamed { from, to } => {
+                vec![Span::raw(format!("Renamed \"{from}\" -> \"{to}\""))]
+            }
+            TaskChange::Status { from, to } => vec![
+                Span::raw("Status "),
+                Span::styled(from.clone(), Style::new().fg(Task::get_status_color(from))),
+                Span::raw(" -> "),
+                Span::styled(to.clone(), Style::new().fg(Task::get_status_color(to))),
+            ],
+            TaskChange::Priority { from, to } => vec![Span::raw(format!(
+                "Priority {} -> {}",
+                get_priority_repr(*from),
+                get_priority_repr(*to)
+            ))],
+            TaskChange::Recurred { due_date } => {
+                vec![Span::raw(format!("Next occurrence due on {due_date}"))]
+            }
+            TaskChange::Moved { from, to } => {
+                vec![Span::raw(format!("Moved from \"{from}\" to \"{to}\""))]
+            }
+            TaskChange::DueDate { from, to } => vec![Span::raw(format!(
+                "Due date {} -> {}",
+                get_due_date_repr(from),
+                get_due_date_repr(to)
+            ))],
+            TaskChange::Recurrence { from, to } => vec![Span::raw(format!(
+                "Recurrence {} -> {}",
+                get_recurrence_repr(from),
+                get_recurrence_repr(to)
+            ))],
+            TaskChange::Timer { running: true } => vec![Span::raw("Timer started")],
+            TaskChange::Timer { running: false } => vec![Span::raw("Timer stopped")],
+            TaskChange::Focused { minutes } => {
+                vec![Span::raw(format!("Focus session of {minutes} min"))]
+            }
+            TaskChange::Blocked { by } => vec![Span::raw(format!("Blocked by \"{by}\""))],
+            TaskChange::Unblocked { by } => {
+                vec![Span::raw(format!("No longer blocked by \"{by}\""))]
+            }
+        }
+    }
 }
 
 pub const TASK_STATUS_DONE: &str = "Done";
@@ -21,14 +186,20 @@ pub const TASK_STATUS_UP_NEXT: &str = "UpNext";
 pub const TASK_STATUSES: [&'static str; 3] =
     [TASK_STATUS_UP_NEXT, TASK_STATUS_ON_GOING, TASK_STATUS_DONE];
 
-const TASK_STATUSES_SORT_ORDER: [&'static str; 3] =
+pub const TASK_STATUSES_SORT_ORDER: [&'static str; 3] =
     [TASK_STATUS_ON_GOING, TASK_STATUS_UP_NEXT, TASK_STATUS_DONE];
 
 // Ascending order: 1 highest priority; 2 medium; 3 lowest
 pub const TASK_PRIORITIES: [u8; 4] = [1, 2, 3, 0];
 
+// Project receiving the archived tasks, created when missing
+pub const ARCHIVE_PROJECT_TITLE: &str = "Archive";
+
+// Uids created in the same nanosecond are told apart by the counter
+static UID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
 impl Task {
-    fn get_status_color(status: &String) -> ratatui::prelude::Color {
+    pub fn get_status_color(status: &String) -> ratatui::prelude::Color {
         match status.as_str() {
             TASK_STATUS_DONE => return Color::LightGreen,
             TASK_STATUS_ON_GOING => return Color::Yellow,
@@ -63,20 +234,8 @@ impl Task {
         }
     }
 
-    pub fn load_items(app: &mut App, items: &mut Vec<ListItem>) {
-        let tasks = &mut app.projects[app.selected_project_index.selected().unwrap()].tasks;
-
-        let last_task_title_selected = tasks
-            .clone()
-            .get(app.selected_task_index.selected().unwrap_or(0))
-            .unwrap_or(&Task {
-                title: "".to_string(),
-                status: "".to_string(),
-                priority: 0,
-            })
-            .clone()
-            .title;
-
+    // Same order shown in the app
+    pub fn sort(tasks: &mut [Task]) {
         // Sort by status
         tasks.sort_by_key(|t| {
             TASK_STATUSES_SORT_ORDER
@@ -86,6 +245,98 @@ impl Task {
 
         // Sort by priority
         tasks.sort_by_key(|t| TASK_PRIORITIES.into_iter().position(|o| o == t.priority));
+    }
+
+    // Set the timestamps missing in a new or imported task
+    pub fn fill_timestamps(task: &mut Task) {
+        let now = Util::get_now();
+
+        task.created_at.get_or_insert(now);
+        task.updated_at.get_or_insert(now);
+
+        if task.status == TASK_STATUS_DONE {
+            task.completed_at.get_or_insert(now);
+        }
+    }
+
+    // Append the change to the history of the task, a change to the same value is ignored
+    pub fn record(task: &mut Task, change: TaskChange) {
+        match &change {
+            TaskChange::Renamed { from, to }
+            | TaskChange::Status { from, to }
+            | TaskChange::Moved { from, to }
+                if from == to =>
+            {
+                return
+            }
+            TaskChange::Priority { from, to } if from == to => return,
+            TaskChange::DueDate { from, to } if from == to => return,
+            TaskChange::Recurrence { from, to } if from == to => return,
+            _ => {}
+        }
+
+        task.history.push(TaskEvent {
+            at: Util::get_now(),
+            change,
+        });
+
+        Task::update_timestamps(task);
+    }
+
+    // Called after every change of the task
+    pub fn update_timestamps(task: &mut Task) {
+        task.updated_at = Some(Util::get_now());
+
+        if task.status != TASK_STATUS_DONE {
+            task.completed_at = None;
+        }
+
+        Task::fill_timestamps(task);
+    }
+
+    pub fn is_timer_running(task: &Task) -> bool {
+        task.time_entries.last().is_some_and(|e| e.end.is_none())
+    }
+
+    fn start_timer(task: &mut Task) {
+        if !Task::is_timer_running(task) {
+            task.time_entries.push(TimeEntry {
+                start: Util::get_now(),
+                end: None,
+            });
+            Task::record(task, TaskChange::Timer { running: true });
+        }
+    }
+
+    fn stop_timer(task: &mut Task) {
+        if let Some(entry) = task.time_entries.last_mut().filter(|e| e.end.is_none()) {
+            entry.end = Some(Util::get_now());
+            Task::record(task, TaskChange::Timer { running: false });
+        }
+    }
+
+    // Sum of the tracked intervals, the running one until now
+    pub fn get_tracked_time(task: &Task) -> TimeDelta {
+        task.time_entries
+            .iter()
+            .map(|e| e.end.unwrap_or(Util::get_now()) - e.start)
+            .sum()
+    }
+
+    pub fn load_items(app: &mut App, items: &mut Vec<ListItem>) {
+        let open_uids = Task::get_open_uids(&app.projects);
+        let show_task_age = app.config.ui.show_task_age;
+        let marked_tasks = app.marked_tasks.clone();
+        let tasks = &mut app.projects[app.selected_project_index.selected().unwrap()].tasks;
+
+        let last_task_title_selected = tasks
+            .clone()
+            .get(app.selected_task_index.selected().unwrap_or(0))
+            .unwrap_or(&Task::default())
+            .clone()
+            .title;
+
+        Task::sort(tasks);
 
         let new_index = tasks
             .into_iter()
@@ -94,41 +345,325 @@ impl Task {
 
         items.clear();
 
-        for task in tasks.iter() {
-            let modifier = if task.status == TASK_STATUS_DONE {
-                Modifier::CROSSED_OUT
+        let today = Local::now().date_naive();
+
+        for (index, task) in tasks.iter().enumerate() {
+            let mut repr = Task::get_repr(task, &open_uids, show_task_age, today);
+
+            if marked_tasks.contains(&index) {
+                repr.insert(0, Span::styled("● ", Style::new().fg(Color::LightCyan)));
+            }
+
+            items.push(ListItem::from(Line::from(repr)))
+        }
+
+        app.selected_task_index.select(Some(new_index))
+    }
+
+    // Row of the task in the lists
+    pub fn get_repr(
+        task: &Task,
+        open_uids: &[String],
+        show_task_age: bool,
+        today: NaiveDate,
+    ) -> Vec<Span<'static>> {
+        let modifier = if task.status == TASK_STATUS_DONE {
+            Modifier::CROSSED_OUT
+        } else {
+            Modifier::empty()
+        };
+
+        let mut repr = vec![
+            Span::styled(
+                format!("[{}] ", task.status),
+                Style::default()
+                    .fg(Task::get_status_color(&task.status))
+                    .add_modifier(modifier),
+            ),
+            Span::styled(task.title.clone(), Style::default().add_modifier(modifier)),
+        ];
+
+        if task.blocked_by.iter().any(|uid| open_uids.contains(uid)) {
+            repr.push(Span::styled(" [blocked]", Style::new().fg(Color::Red)));
+        }
+
+        if let Some(due_date) = task.due_date {
+            let color = if task.status != TASK_STATUS_DONE && due_date < today {
+                Color::Red
             } else {
-                Modifier::empty()
+                Color::DarkGray
             };
 
-            let mut repr = vec![
+            repr.push(Span::styled(
+                format!(" due {due_date}"),
+                Style::new().fg(color),
+            ));
+        }
+
+        if let Some(recurrence) = &task.recurrence {
+            repr.push(Span::styled(
+                format!(" ↻ {}", recurrence.get_repr()),
+                Style::new().fg(Color::DarkGray),
+            ));
+        }
+
+        if !task.checklist.is_empty() {
+            let done = task.checklist.iter().filter(|i| i.done).count();
+
+            repr.push(Span::styled(
+                format!(" [{done}/{}]", task.checklist.len()),
+                Style::new().fg(Color::DarkGray),
+            ));
+        }
+
+        if show_task_age {
+            if let Some(created_at) = task.created_at {
+                repr.push(Span::styled(
+                    format!(" ({})", Util::get_age(created_at)),
+                    Style::new().fg(Color::DarkGray),
+                ));
+            }
+        }
+
+        if !task.time_entries.is_empty() {
+            let color = if Task::is_timer_running(task) {
+                Color::Yellow
+            } else {
+                Color::DarkGray
+            };
+
+            repr.push(Span::styled(
+                format!(" {}", Util::get_clock_repr(Task::get_tracked_time(task))),
+                Style::new().fg(color),
+            ));
+        }
+
+        for tag in task.tags.iter() {
+            repr.push(Span::styled(
+                format!(" #{tag}"),
+                Style::new().fg(Color::DarkGray),
+            ));
+        }
+
+        if task.priority != 0 {
+            let priority_repr = vec![Span::styled(
+                format!("[{}] ", Util::get_priority_indicator(task.priority)),
+                Style::new().fg(Color::Red),
+            )];
+            repr = [priority_repr, repr].concat()
+        }
+
+        repr
+    }
+
+    // Uids of the tasks not done yet, the ones blocking their dependents
+    pub fn get_open_uids(projects: &[Project]) -> Vec<String> {
+        projects
+            .iter()
+            .flat_map(|p| p.tasks.iter())
+            .filter(|t| t.status != TASK_STATUS_DONE)
+            .filter_map(|t| t.uid.clone())
+            .collect()
+    }
+
+    // Project and task of each link, the ones of deleted tasks are skipped
+    pub fn get_blockers<'a>(task: &Task, projects: &'a [Project]) -> Vec<(&'a Project, &'a Task)> {
+        projects
+            .iter()
+            .flat_map(|p| p.tasks.iter().map(move |t| (p, t)))
+            .filter(|(_, t)| {
+                t.uid
+                    .as_ref()
+                    .is_some_and(|uid| task.blocked_by.contains(uid))
+            })
+            .collect()
+    }
+
+    pub fn get_blocked_tasks<'a>(
+        task: &Task,
+        projects: &'a [Project],
+    ) -> Vec<(&'a Project, &'a Task)> {
+        let Some(uid) = &task.uid else {
+            return vec![];
+        };
+
+        projects
+            .iter()
+            .flat_map(|p| p.tasks.iter().map(move |t| (p, t)))
+            .filter(|(_, t)| t.blocked_by.contains(uid))
+            .collect()
+    }
+
+    pub fn get_or_create_uid(task: &mut Task) -> String {
+        task.uid
+            .get_or_insert_with(|| {
+                let seed = format!(
+                    "{} {} {}",
+                    task.title,
+                    Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+                    UID_COUNTER.fetch_add(1, Ordering::Relaxed)
+                );
+
+                format!("{:016x}", Interop::get_hash(&seed))
+            })
+            .clone()
+    }
+
+    // Whether the task waits for the one with the uid, directly or through its blockers
+    fn is_blocked_by(task: &Task, uid: &str, projects: &[Project]) -> bool {
+        let mut pending: Vec<&String> = task.blocked_by.iter().collect();
+        let mut visited: Vec<&String> = vec![];
+
+        while let Some(blocker_uid) = pending.pop() {
+            if blocker_uid == uid {
+                return true;
+            }
+
+            if visited.contains(&blocker_uid) {
+                continue;
+            }
+            visited.push(blocker_uid);
+
+            for blocker in projects
+                .iter()
+                .flat_map(|p| p.tasks.iter())
+                .filter(|t| t.uid.as_ref() == Some(blocker_uid))
+            {
+                pending.extend(blocker.blocked_by.iter());
+            }
+        }
+
+        false
+    }
+
+    // Whether linking the blocker would close a cycle: the blocker already waits for the task.
+    // Unlinking is always allowed, and a task without uid is not waited for by anyone
+    fn is_cycle(task: &Task, blocker_uid: &str, projects: &[Project]) -> bool {
+        let Some(uid) = &task.uid else {
+            return false;
+        };
+
+        !task.blocked_by.iter().any(|u| u == blocker_uid)
+            && projects
+                .iter()
+                .flat_map(|p| p.tasks.iter())
+                .filter(|t| t.uid.as_deref() == Some(blocker_uid))
+                .any(|blocker| Task::is_blocked_by(blocker, uid, projects))
+    }
+
+    // Position (project and task) of every task that can block the current one.
+    // Done tasks are left out, unless they are already linked so they can be unlinked
+    pub fn get_blocker_candidates(app: &App) -> Vec<(usize, usize)> {
+        let current = (
+            app.selected_project_index.selected().unwrap(),
+            app.selected_task_index.selected().unwrap(),
+        );
+        let blocked_by = &app.projects[current.0].tasks[current.1].blocked_by;
+
+        app.projects
+            .iter()
+            .enumerate()
+            .flat_map(|(p, project)| (0..project.tasks.len()).map(move |t| (p, t)))
+            .filter(|position| *position != current)
+            .filter(|(p, t)| {
+                let candidate = &app.projects[*p].tasks[*t];
+
+                candidate.status != TASK_STATUS_DONE
+                    || candidate
+                        .uid
+                        .as_ref()
+                        .is_some_and(|uid| blocked_by.contains(uid))
+            })
+            .collect()
+    }
+
+    pub fn load_blocker_items(app: &mut App, items: &mut Vec<ListItem>) {
+        let task = Task::get_current(app).clone();
+
+        items.clear();
+
+        for (p, t) in Task::get_blocker_candidates(app) {
+            let project = &app.projects[p];
+            let candidate = &project.tasks[t];
+            let is_blocker = candidate
+                .uid
+                .as_ref()
+                .is_some_and(|uid| task.blocked_by.contains(uid));
+
+            items.push(ListItem::from(Line::from(vec![
+                Span::raw(if is_blocker { "[x] " } else { "[ ] " }),
+                Span::styled(
+                    format!("{}: ", project.title),
+                    Style::new().fg(Color::DarkGray),
+                ),
+                Span::raw(candidate.title.clone()),
                 Span::styled(
-                    format!("[{}] ", task.status),
-                    Style::default()
-                        .fg(Task::get_status_color(&task.status))
-                        .add_modifier(modifier),
+                    format!(" [{}]", candidate.status),
+                    Style::new().fg(Task::get_status_color(&candidate.status)),
                 ),
-                Span::styled(task.title.clone(), Style::default().add_modifier(modifier)),
-            ];
+            ])))
+        }
 
-            if task.priority != 0 {
-                let priority_repr = vec![Span::styled(
-                    format!("[{}] ", Util::get_priority_indicator(task.priority)),
-                    Style::new().fg(Color::Red),
-                )];
-                repr = [priority_repr, repr].concat()
-            }
+        // The unlinked done task leaves the list
+        let index = app
+            .selected_blocker_task_index
+            .selected()
+            .unwrap_or(0)
+            .min(items.len().saturating_sub(1));
+        app.selected_blocker_task_index.select(Some(index))
+    }
+
+    // Link or unlink the candidate (by its position in `get_blocker_candidates`) as a blocker
+    pub fn toggle_blocker(app: &mut App, items: &mut Vec<ListItem>, candidate_index: usize) {
+        let Some(&(blocker_project_index, blocker_index)) =
+            Task::get_blocker_candidates(app).get(candidate_index)
+        else {
+            return;
+        };
+        let project_index = app.selected_project_index.selected().unwrap();
+        let task_index = app.selected_task_index.selected().unwrap();
+
+        let mut internal_projects = app.projects.clone();
+
+        let blocker = &mut internal_projects[blocker_project_index].tasks[blocker_index];
+        let blocker_uid = Task::get_or_create_uid(blocker);
+        let blocker_title = blocker.title.clone();
 
-            let line = Line::from(repr);
+        let task = &internal_projects[project_index].tasks[task_index];
 
-            items.push(ListItem::from(line))
+        if Task::is_cycle(task, &blocker_uid, &internal_projects) {
+            app.info_message = Some(format!(
+                "\"{blocker_title}\" can't block this task, it's waiting for it"
+            ));
+            return;
         }
 
-        app.selected_task_index.select(Some(new_index))
+        let task = &mut internal_projects[project_index].tasks[task_index];
+        Task::get_or_create_uid(task);
+
+        match task.blocked_by.iter().position(|uid| *uid == blocker_uid) {
+            Some(position) => {
+                task.blocked_by.remove(position);
+                Task::record(task, TaskChange::Unblocked { by: blocker_title });
+            }
+            None => {
+                task.blocked_by.push(blocker_uid);
+                Task::record(task, TaskChange::Blocked { by: blocker_title });
+            }
+        }
+
+        app.store
+            .save_task(&internal_projects, blocker_project_index, blocker_index);
+        app.store
+            .save_task(&internal_projects, project_index, task_index);
+        Task::reload(app, items, internal_projects)
     }
 
-    pub fn reload(app: &mut App, items: &mut Vec<ListItem>) {
-        app.projects = Json::read();
+    // Show the saved board
+    pub fn reload(app: &mut App, items: &mut Vec<ListItem>, internal_projects: Vec<Project>) {
+        // Marks are positions in the list, a change can reorder it
+        Task::clear_marks(app);
+        app.projects = app.store.get_saved_projects(internal_projects);
         Task::load_items(app, items)
     }
 
@@ -146,69 +681,567 @@ impl Task {
             return;
         }
 
-        let new_task = Task {
+        let mut new_task = Task {
+            id: None,
             title: value.to_string(),
             status: TASK_STATUS_UP_NEXT.to_string(),
             priority: 0,
+            ..Default::default()
         };
+        Task::record(&mut new_task, TaskChange::Created);
+
+        let project_index = app.selected_project_index.selected().unwrap();
 
         let mut internal_projects = app.projects.clone();
-        internal_projects[app.selected_project_index.selected().unwrap()]
-            .tasks
-            .push(new_task);
+        internal_projects[project_index].tasks.push(new_task);
 
-        Json::write(internal_projects);
-        Task::reload(app, items)
+        app.store.save_task(
+            &internal_projects,
+            project_index,
+            internal_projects[project_index].tasks.len() - 1,
+        );
+        Task::reload(app, items, internal_projects)
     }
 
     pub fn rename(app: &mut App, items: &mut Vec<ListItem>, value: &str) {
         let mut internal_projects = app.projects.clone();
 
-        internal_projects[app.selected_project_index.selected().unwrap()].tasks
-            [app.selected_task_index.selected().unwrap()]
-        .title = value.to_string();
+        let task = &mut internal_projects[app.selected_project_index.selected().unwrap()].tasks
+            [app.selected_task_index.selected().unwrap()];
+        let change = TaskChange::Renamed {
+            from: task.title.clone(),
+            to: value.to_string(),
+        };
+        task.title = value.to_string();
+        Task::record(task, change);
 
-        Json::write(internal_projects);
-        Task::reload(app, items)
+        app.store.save_task(
+            &internal_projects,
+            app.selected_project_index.selected().unwrap(),
+            app.selected_task_index.selected().unwrap(),
+        );
+        Task::reload(app, items, internal_projects)
     }
 
     pub fn change_status(app: &mut App, items: &mut Vec<ListItem>, value: &str) {
         let mut internal_projects = app.projects.clone();
+
+        let project_index = app.selected_project_index.selected().unwrap();
+        let task_index = app.selected_task_index.selected().unwrap();
+        let next_occurrence = Task::set_status(
+            app,
+            &mut internal_projects[project_index].tasks[task_index],
+            value,
+        );
+
+        app.store
+            .save_task(&internal_projects, project_index, task_index);
+
+        if let Some(next_task) = next_occurrence {
+            internal_projects[project_index].tasks.push(next_task);
+
+            app.store.save_task(
+                &internal_projects,
+                project_index,
+                internal_projects[project_index].tasks.len() - 1,
+            );
+        }
+        Task::reload(app, items, internal_projects)
+    }
+
+    // Change the status of the task and return the next occurrence of a recurring task
+    fn set_status(app: &mut App, task: &mut Task, value: &str) -> Option<Task> {
         let status = value.to_string();
 
-        internal_projects[app.selected_project_index.selected().unwrap()].tasks
-            [app.selected_task_index.selected().unwrap()]
-        .status = status.clone();
+        let change = TaskChange::Status {
+            from: task.status.clone(),
+            to: status.clone(),
+        };
+        task.status = status.clone();
+        Task::record(task, change);
+
+        let open_blockers: Vec<String> = Task::get_blockers(task, &app.projects)
+            .into_iter()
+            .filter(|(_, t)| t.status != TASK_STATUS_DONE)
+            .map(|(p, t)| format!("{}: {}", p.title, t.title))
+            .collect();
+
+        if status == TASK_STATUS_ON_GOING && !open_blockers.is_empty() {
+            let warning = format!(
+                "\"{}\" is blocked by:\n{}",
+                task.title,
+                open_blockers.join("\n")
+            );
+
+            // The warnings of a bulk change are shown together
+            app.info_message = Some(match app.info_message.take() {
+                Some(message) => format!("{message}\n{warning}"),
+                None => warning,
+            });
+        }
+
+        let next_occurrence = if status == TASK_STATUS_DONE {
+            Task::get_next_occurrence(task)
+        } else {
+            None
+        };
+
+        // A timer runs only while the task is OnGoing
+        if status == TASK_STATUS_ON_GOING && app.config.time.auto_start {
+            Task::start_timer(task);
+        } else if status != TASK_STATUS_ON_GOING {
+            Task::stop_timer(task);
+        }
 
-        if status == TASK_STATUS_DONE {
-            internal_projects[app.selected_project_index.selected().unwrap()].tasks
-                [app.selected_task_index.selected().unwrap()]
-            .priority = 0
+        if status == TASK_STATUS_DONE && task.priority != 0 {
+            let change = TaskChange::Priority {
+                from: task.priority,
+                to: 0,
+            };
+            task.priority = 0;
+            Task::record(task, change);
         }
 
-        Json::write(internal_projects);
-        Task::reload(app, items)
+        next_occurrence
+    }
+
+    // Copy of a recurring task for its next due date, the done task doesn't recur anymore
+    fn get_next_occurrence(task: &mut Task) -> Option<Task> {
+        let recurrence = task.recurrence.take()?;
+        let due_date = recurrence.get_next_due(task.due_date, Local::now().date_naive());
+
+        Task::record(task, TaskChange::Recurred { due_date });
+
+        let mut next_task = Task {
+            id: None,
+            title: task.title.clone(),
+            status: TASK_STATUS_UP_NEXT.to_string(),
+            priority: task.priority,
+            description: task.description.clone(),
+            tags: task.tags.clone(),
+            checklist: task
+                .checklist
+                .iter()
+                .map(|i| ChecklistItem {
+                    title: i.title.clone(),
+                    done: false,
+                })
+                .collect(),
+            due_date: Some(due_date),
+            recurrence: Some(recurrence),
+            ..Default::default()
+        };
+        Task::record(&mut next_task, TaskChange::Created);
+
+        Some(next_task)
+    }
+
+    // Date as "YYYY-MM-DD", an empty value removes the due date
+    pub fn change_due_date(
+        app: &mut App,
+        items: &mut Vec<ListItem>,
+        value: &str,
+    ) -> Result<(), String> {
+        let due_date = match value.trim() {
+            "" => None,
+            value => match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                Ok(date) => Some(date),
+                Err(_) => return Err(format!("Invalid date \"{value}\", expected YYYY-MM-DD")),
+            },
+        };
+
+        let mut internal_projects = app.projects.clone();
+
+        let task = &mut internal_projects[app.selected_project_index.selected().unwrap()].tasks
+            [app.selected_task_index.selected().unwrap()];
+        let change = TaskChange::DueDate {
+            from: task.due_date,
+            to: due_date,
+        };
+        task.due_date = due_date;
+        Task::record(task, change);
+
+        app.store.save_task(
+            &internal_projects,
+            app.selected_project_index.selected().unwrap(),
+            app.selected_task_index.selected().unwrap(),
+        );
+        Task::reload(app, items, internal_projects);
+
+        Ok(())
+    }
+
+    // Rule as shown by `Recurrence::get_repr`, an empty value removes it
+    pub fn change_recurrence(
+        app: &mut App,
+        items: &mut Vec<ListItem>,
+        value: &str,
+    ) -> Result<(), String> {
+        let mut internal_projects = app.projects.clone();
+
+        let task = &mut internal_projects[app.selected_project_index.selected().unwrap()].tasks
+            [app.selected_task_index.selected().unwrap()];
+
+        let recurrence = match value.trim() {
+            "" => None,
+            value => Some(Recurrence::parse(
+                value,
+                task.due_date.unwrap_or(Local::now().date_naive()),
+            )?),
+        };
+        let change = TaskChange::Recurrence {
+            from: task.recurrence.clone(),
+            to: recurrence.clone(),
+        };
+        task.recurrence = recurrence;
+        Task::record(task, change);
+
+        app.store.save_task(
+            &internal_projects,
+            app.selected_project_index.selected().unwrap(),
+            app.selected_task_index.selected().unwrap(),
+        );
+        Task::reload(app, items, internal_projects);
+
+        Ok(())
     }
 
     pub fn change_priority(app: &mut App, items: &mut Vec<ListItem>, value: u8) {
         let mut internal_projects = app.projects.clone();
 
-        internal_projects[app.selected_project_index.selected().unwrap()].tasks
-            [app.selected_task_index.selected().unwrap()]
-        .priority = value;
+        let task = &mut internal_projects[app.selected_project_index.selected().unwrap()].tasks
+            [app.selected_task_index.selected().unwrap()];
+        let change = TaskChange::Priority {
+            from: task.priority,
+            to: value,
+        };
+        task.priority = value;
+        Task::record(task, change);
+
+        app.store.save_task(
+            &internal_projects,
+            app.selected_project_index.selected().unwrap(),
+            app.selected_task_index.selected().unwrap(),
+        );
+        Task::reload(app, items, internal_projects)
+    }
+
+    pub fn toggle_timer(app: &mut App, items: &mut Vec<ListItem>) {
+        let mut internal_projects = app.projects.clone();
+
+        let task = &mut internal_projects[app.selected_project_index.selected().unwrap()].tasks
+            [app.selected_task_index.selected().unwrap()];
+
+        if Task::is_timer_running(task) {
+            Task::stop_timer(task);
+        } else {
+            Task::start_timer(task);
+        }
 
-        Json::write(internal_projects);
-        Task::reload(app, items)
+        app.store.save_task(
+            &internal_projects,
+            app.selected_project_index.selected().unwrap(),
+            app.selected_task_index.selected().unwrap(),
+        );
+        Task::reload(app, items, internal_projects)
+    }
+
+    pub fn log_focus_session(
+        app: &mut App,
+        items: &mut Vec<ListItem>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) {
+        let mut internal_projects = app.projects.clone();
+
+        let task = &mut internal_projects[app.selected_project_index.selected().unwrap()].tasks
+            [app.selected_task_index.selected().unwrap()];
+        task.focus_sessions.push(TimeEntry {
+            start,
+            end: Some(end),
+        });
+        Task::record(
+            task,
+            TaskChange::Focused {
+                minutes: (end - start).num_minutes(),
+            },
+        );
+
+        app.store.save_task(
+            &internal_projects,
+            app.selected_project_index.selected().unwrap(),
+            app.selected_task_index.selected().unwrap(),
+        );
+        Task::reload(app, items, internal_projects)
+    }
+
+    // Mark or unmark the selected task for the bulk actions
+    pub fn toggle_mark(app: &mut App, items: &mut Vec<ListItem>) {
+        let index = app.selected_task_index.selected().unwrap();
+
+        match app.marked_tasks.iter().position(|i| *i == index) {
+            Some(position) => {
+                app.marked_tasks.remove(position);
+            }
+            None => app.marked_tasks.push(index),
+        }
+
+        app.mark_anchor = Some(index);
+        Task::load_items(app, items)
+    }
+
+    // Mark the tasks from the last (un)marked one to the selected one
+    pub fn mark_range(app: &mut App, items: &mut Vec<ListItem>) {
+        let index = app.selected_task_index.selected().unwrap();
+        let anchor = app.mark_anchor.unwrap_or(index);
+
+        for i in anchor.min(index)..=anchor.max(index) {
+            if !app.marked_tasks.contains(&i) {
+                app.marked_tasks.push(i);
+            }
+        }
+
+        app.mark_anchor = Some(index);
+        Task::load_items(app, items)
+    }
+
+    pub fn clear_marks(app: &mut App) {
+        app.marked_tasks.clear();
+        app.mark_anchor = None;
+    }
+
+    // Indexes of the tasks a bulk action applies to: the marked ones, or the selected one
+    pub fn get_targets(app: &App) -> Vec<usize> {
+        let mut targets = if app.marked_tasks.is_empty() {
+            vec![app.selected_task_index.selected().unwrap()]
+        } else {
+            app.marked_tasks.clone()
+        };
+
+        targets.sort();
+        targets
+    }
+
+    // Same as `change_status` on every target, the bulk actions write the board once
+    pub fn change_status_marked(app: &mut App, items: &mut Vec<ListItem>, value: &str) {
+        let mut internal_projects = app.projects.clone();
+        let project_index = app.selected_project_index.selected().unwrap();
+
+        for task_index in Task::get_targets(app) {
+            let next_occurrence = Task::set_status(
+                app,
+                &mut internal_projects[project_index].tasks[task_index],
+                value,
+            );
+
+            // Appended, the indexes of the other targets don't change
+            if let Some(next_task) = next_occurrence {
+                internal_projects[project_index].tasks.push(next_task);
+            }
+        }
+
+        app.store.save_all(&internal_projects);
+        Task::reload(app, items, internal_projects)
+    }
+
+    pub fn change_priority_marked(app: &mut App, items: &mut Vec<ListItem>, value: u8) {
+        let mut internal_projects = app.projects.clone();
+        let project_index = app.selected_project_index.selected().unwrap();
+
+        for task_index in Task::get_targets(app) {
+            let task = &mut internal_projects[project_index].tasks[task_index];
+            let change = TaskChange::Priority {
+                from: task.priority,
+                to: value,
+            };
+            task.priority = value;
+            Task::record(task, change);
+        }
+
+        app.store.save_all(&internal_projects);
+        Task::reload(app, items, internal_projects)
+    }
+
+    pub fn delete_marked(app: &mut App, items: &mut Vec<ListItem>) {
+        let mut internal_projects = app.projects.clone();
+        let project_index = app.selected_project_index.selected().unwrap();
+
+        // From the last one, so the indexes of the others don't change
+        for task_index in Task::get_targets(app).into_iter().rev() {
+            internal_projects[project_index].tasks.remove(task_index);
+        }
+
+        app.store.save_all(&internal_projects);
+        Task::reload(app, items, internal_projects)
+    }
+
+    // Index of the projects the tasks can be moved to
+    pub fn get_move_projects(app: &App) -> Vec<usize> {
+        let project_index = app.selected_project_index.selected().unwrap();
+
+        (0..app.projects.len())
+            .filter(|i| *i != project_index)
+            .collect()
+    }
+
+    pub fn load_move_items(app: &App, items: &mut Vec<ListItem>) {
+        items.clear();
+
+        for project_index in Task::get_move_projects(app) {
+            items.push(ListItem::from(app.projects[project_index].title.clone()))
+        }
+    }
+
+    pub fn move_marked(app: &mut App, items: &mut Vec<ListItem>, to_project_index: usize) {
+        if to_project_index == app.selected_project_index.selected().unwrap() {
+            return;
+        }
+
+        let mut internal_projects = app.projects.clone();
+        Task::move_targets(app, &mut internal_projects, to_project_index);
+
+        app.store.save_all(&internal_projects);
+        Task::reload(app, items, internal_projects)
+    }
+
+    // Move the tasks at the end of the other project, keeping their order
+    fn move_targets(app: &App, internal_projects: &mut [Project], to_project_index: usize) {
+        let project_index = app.selected_project_index.selected().unwrap();
+        let from = internal_projects[project_index].title.clone();
+        let to = internal_projects[to_project_index].title.clone();
+
+        let mut moved_tasks: Vec<Task> = vec![];
+
+        for task_index in Task::get_targets(app).into_iter().rev() {
+            let mut task = internal_projects[project_index].tasks.remove(task_index);
+            Task::record(
+                &mut task,
+                TaskChange::Moved {
+                    from: from.clone(),
+                    to: to.clone(),
+                },
+            );
+
+            moved_tasks.insert(0, task);
+        }
+
+        internal_projects[to_project_index]
+            .tasks
+            .extend(moved_tasks);
+    }
+
+    // Move the tasks into the archive project
+    pub fn archive_marked(app: &mut App, items: &mut Vec<ListItem>) {
+        if Project::get_current(app).title == ARCHIVE_PROJECT_TITLE {
+            app.info_message = Some("The tasks are already archived".to_string());
+            return;
+        }
+
+        let mut internal_projects = app.projects.clone();
+
+        // Created and filled in the same write
+        let archive_index = match internal_projects
+            .iter()
+            .position(|p| p.title == ARCHIVE_PROJECT_TITLE)
+        {
+            Some(index) => index,
+            None => {
+                internal_projects.push(Project {
+                    id: None,
+                    title: ARCHIVE_PROJECT_TITLE.to_string(),
+                    tasks: vec![],
+                });
+                internal_projects.len() - 1
+            }
+        };
+
+        Task::move_targets(app, &mut internal_projects, archive_index);
+
+        app.store.save_all(&internal_projects);
+        Task::reload(app, items, internal_projects)
     }
 
     pub fn delete(app: &mut App, items: &mut Vec<ListItem>) {
         let mut internal_projects = app.projects.clone();
 
-        internal_projects[app.selected_project_index.selected().unwrap()]
+        let removed_task = internal_projects[app.selected_project_index.selected().unwrap()]
             .tasks
             .remove(app.selected_task_index.selected().unwrap());
 
-        Json::write(internal_projects);
-        Task::reload(app, items)
+        app.store.delete_task(
+            &internal_projects,
+            app.selected_project_index.selected().unwrap(),
+            &removed_task,
+        );
+        Task::reload(app, items, internal_projects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Task with the uid, waiting for the blockers
+    fn get_task(uid: &str, blocked_by: &[&str]) -> Task {
+        Task {
+            uid: Some(uid.to_string()),
+            title: uid.to_string(),
+            blocked_by: blocked_by.iter().map(|u| u.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn get_project(title: &str, tasks: Vec<Task>) -> Project {
+        Project {
+            id: None,
+            title: title.to_string(),
+            tasks,
+        }
+    }
+
+    #[test]
+    fn direct_cycle() {
+        let projects = vec![get_project(
+            "Home",
+            vec![get_task("a", &["b"]), get_task("b", &[])],
+        )];
+        let (a, b) = (&projects[0].tasks[0], &projects[0].tasks[1]);
+
+        assert!(Task::is_blocked_by(a, "b", &projects));
+        assert!(!Task::is_blocked_by(b, "a", &projects));
+        // "a" can't block "b", it's waiting for it
+        assert!(Task::is_cycle(b, "a", &projects));
+    }
+
+    #[test]
+    fn transitive_cycle_between_projects() {
+        let projects = vec![
+            get_project("Home", vec![get_task("a", &["b"]), get_task("c", &[])]),
+            get_project("Work", vec![get_task("b", &["c"]), get_task("d", &[])]),
+        ];
+        let (a, c) = (&projects[0].tasks[0], &projects[0].tasks[1]);
+
+        assert!(Task::is_blocked_by(a, "c", &projects));
+        assert!(Task::is_cycle(c, "a", &projects));
+        assert!(Task::is_cycle(c, "b", &projects));
+        assert!(!Task::is_cycle(c, "d", &projects));
+        assert!(!Task::is_cycle(a, "d", &projects));
+    }
+
+    #[test]
+    fn unlinking_is_not_a_cycle() {
+        // A cycle left by an older version, or by an import, can still be undone
+        let projects = vec![get_project(
+            "Home",
+            vec![get_task("a", &["b"]), get_task("b", &["a"])],
+        )];
+        let a = &projects[0].tasks[0];
+
+        assert!(Task::is_blocked_by(a, "a", &projects));
+        assert!(!Task::is_cycle(a, "b", &projects));
+
+        // A task without uid is not waited for by anyone
+        let task = Task::default();
+        assert!(!Task::is_cycle(&task, "a", &projects));
     }
 }
@@ -1,3 +1,5 @@
+use chrono::{DateTime, SubsecRound, TimeDelta, Utc};
+
 use crate::task::TASK_PRIORITIES;
 
 pub struct Util;
@@ -25,4 +27,55 @@ impl Util {
 
         "!!!".chars().take((priority_value).into()).collect()
     }
+
+    // Reverse of `get_priority_indicator`: "!!!" => 1, "!!" => 2, "!" => 3, "" => 0
+    pub fn get_priority_from_indicator(indicator: &str) -> Option<u8> {
+        if indicator.chars().any(|c| c != '!') {
+            return None;
+        }
+
+        TASK_PRIORITIES.into_iter().rev().nth(indicator.len())
+    }
+
+    // Current time without the fractions of second, to keep the saved files readable
+    pub fn get_now() -> DateTime<Utc> {
+        Utc::now().trunc_subsecs(0)
+    }
+
+    // Time elapsed since the date in a short form: "5m", "3h", "2d", "6w"
+    pub fn get_age(date: DateTime<Utc>) -> String {
+        Util::get_duration_repr(Util::get_now() - date)
+    }
+
+    pub fn get_duration_repr(duration: TimeDelta) -> String {
+        match duration {
+            a if a.num_hours() < 1 => format!("{}m", a.num_minutes().max(0)),
+            a if a.num_days() < 1 => format!("{}h", a.num_hours()),
+            a if a.num_weeks() < 2 => format!("{}d", a.num_days()),
+            a => format!("{}w", a.num_weeks()),
+        }
+    }
+
+    // Tracked time as a clock: "0:05:09", "12:30:00"
+    pub fn get_clock_repr(duration: TimeDelta) -> String {
+        let seconds = duration.num_seconds().max(0);
+
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            (seconds % 3600) / 60,
+            seconds % 60
+        )
+    }
+}
+
+// Shared by the tests of the other modules
+#[cfg(test)]
+pub mod test_helpers {
+    use chrono::NaiveDate;
+
+    // "2026-10-15" => the date
+    pub fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
 }
@@ -1,13 +1,26 @@
+use chrono::{Local, TimeDelta};
 use ratatui::{
-    layout::{Alignment, Rect},
-    style::{Modifier, Style},
-    text::{Line, Text},
-    widgets::{Block, Clear, HighlightSpacing, List, ListItem, Paragraph, Wrap},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{
+        Bar, BarChart, BarGroup, Block, Cell, Clear, Gauge, HighlightSpacing, List, ListItem,
+        Paragraph, Row, Table, Wrap,
+    },
     Frame,
 };
 use tui_input::Input;
 
-use crate::{project::Project, task::Task, ui::Ui, util::Util, App, ViewMode};
+use crate::{
+    calendar::Calendar,
+    focus::FocusPhase,
+    project::Project,
+    statistics::Statistics,
+    task::{Task, ARCHIVE_PROJECT_TITLE},
+    ui::Ui,
+    util::Util,
+    App, ViewMode,
+};
 
 pub struct View {}
 
@@ -27,26 +40,153 @@ impl View {
         Ui::create_modal(f, 30, 4, area, widget)
     }
 
+    pub fn show_info_modal(message: &str, f: &mut Frame, area: Rect) {
+        let widget = Paragraph::new(message)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered());
+
+        // Borders plus one row per line
+        let height = (message.lines().count() as u16 + 2).max(4);
+
+        Ui::create_modal(f, 30, height, area, widget)
+    }
+
     pub fn show_rename_item_modal(f: &mut Frame, area: Rect, input: &Input) {
         Ui::create_input_modal("Rename", f, area, input)
     }
 
+    pub fn show_due_date_modal(f: &mut Frame, area: Rect, input: &Input) {
+        Ui::create_input_modal("Due date (YYYY-MM-DD)", f, area, input)
+    }
+
+    pub fn show_recurrence_modal(f: &mut Frame, area: Rect, input: &Input) {
+        Ui::create_input_modal(
+            "Repeat (daily, weekly mon,thu, monthly 15, every 3 days)",
+            f,
+            area,
+            input,
+        )
+    }
+
+    pub fn show_select_task_blockers_modal(
+        app: &mut App,
+        blocker_items: &[ListItem],
+        f: &mut Frame,
+        area: Rect,
+    ) {
+        let area = Ui::create_rect_area(60, 15, area);
+
+        let blockers_list_widget = List::new(blocker_items.to_vec())
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol("> ")
+            .highlight_spacing(HighlightSpacing::Always)
+            .block(Block::bordered().title(Util::get_spaced_title("Blocked by")));
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(blockers_list_widget, area, app.use_state())
+    }
+
+    pub fn show_task_dependencies_modal(app: &mut App, f: &mut Frame, area: Rect) {
+        let task = Task::get_current(app).clone();
+
+        let get_lines = |links: Vec<(&Project, &Task)>| -> Vec<Line> {
+            if links.is_empty() {
+                return vec![Line::styled("  none", Style::new().fg(Color::DarkGray))];
+            }
+
+            links
+                .into_iter()
+                .map(|(project, linked)| {
+                    Line::from(vec![
+                        Span::styled(
+                            format!("  {}: ", project.title),
+                            Style::new().fg(Color::DarkGray),
+                        ),
+                        Span::raw(linked.title.clone()),
+                        Span::styled(
+                            format!(" [{}]", linked.status),
+                            Style::new().fg(Task::get_status_color(&linked.status)),
+                        ),
+                    ])
+                })
+                .collect()
+        };
+
+        let lines = [
+            vec![Line::styled(
+                "Blocked by",
+                Style::new().add_modifier(Modifier::BOLD),
+            )],
+            get_lines(Task::get_blockers(&task, &app.projects)),
+            vec![
+                Line::raw(""),
+                Line::styled("Blocks", Style::new().add_modifier(Modifier::BOLD)),
+            ],
+            get_lines(Task::get_blocked_tasks(&task, &app.projects)),
+        ]
+        .concat();
+
+        let widget = Paragraph::new(lines).block(Block::bordered().title(Util::get_spaced_title(
+            &format!("Dependencies: {}", task.title),
+        )));
+
+        Ui::create_modal(f, 60, 15, area, widget)
+    }
+
     pub fn show_delete_item_modal(app: &mut App, f: &mut Frame, area: Rect) {
         let title = match app.view_mode {
-            ViewMode::DeleteTask => &Task::get_current(app).title,
-            ViewMode::DeleteProject => &Project::get_current(app).title,
-            _ => "",
+            ViewMode::DeleteTask if !app.marked_tasks.is_empty() => {
+                format!("{} marked tasks", app.marked_tasks.len())
+            }
+            ViewMode::DeleteTask => format!("\"{}\"", Task::get_current(app).title),
+            ViewMode::DeleteProject => format!("\"{}\"", Project::get_current(app).title),
+            _ => String::new(),
+        };
+
+        Ui::create_question_modal("Are you sure to delete?", &title, "Delete", f, area)
+    }
+
+    pub fn show_archive_tasks_modal(app: &mut App, f: &mut Frame, area: Rect) {
+        let title = if app.marked_tasks.is_empty() {
+            format!("\"{}\"", Task::get_current(app).title)
+        } else {
+            format!("{} marked tasks", app.marked_tasks.len())
         };
 
         Ui::create_question_modal(
-            "Are you sure to delete?",
-            format!("\"{}\"", title).as_str(),
-            "Delete",
+            &format!("Move to the \"{ARCHIVE_PROJECT_TITLE}\" project?"),
+            &title,
+            "Archive",
             f,
             area,
         )
     }
 
+    pub fn show_move_tasks_modal(
+        app: &mut App,
+        move_items: &[ListItem],
+        f: &mut Frame,
+        area: Rect,
+    ) {
+        let area = Ui::create_rect_area(30, 10, area);
+
+        let title = if app.marked_tasks.is_empty() {
+            "Move to".to_string()
+        } else {
+            format!("Move {} tasks to", app.marked_tasks.len())
+        };
+
+        let projects_list_widget = List::new(move_items.to_vec())
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol("> ")
+            .highlight_spacing(HighlightSpacing::Always)
+            .block(Block::bordered().title(Util::get_spaced_title(&title)));
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(projects_list_widget, area, app.use_state())
+    }
+
     pub fn show_select_task_status_modal(
         app: &mut App,
         status_items: &Vec<ListItem>,
@@ -54,6 +194,7 @@ impl View {
         area: Rect,
     ) {
         let area = Ui::create_rect_area(10, 5, area);
+        app.modal_area = area;
 
         let task_status_list_widget = List::new(status_items.clone())
             .highlight_style(Style::default().add_modifier(Modifier::BOLD))
@@ -72,6 +213,7 @@ impl View {
         area: Rect,
     ) {
         let area = Ui::create_rect_area(10, 6, area);
+        app.modal_area = area;
 
         let task_status_list_widget = List::new(priority_items.clone())
             .highlight_style(Style::default().add_modifier(Modifier::BOLD))
@@ -84,11 +226,14 @@ impl View {
     }
 
     pub fn show_items(app: &mut App, items: &Vec<ListItem>, f: &mut Frame, area: Rect) {
+        app.list_area = area;
+
         let block: Block = match app.view_mode {
             ViewMode::ViewProjects
             | ViewMode::AddProject
             | ViewMode::RenameProject
             | ViewMode::DeleteProject => Block::bordered(),
+            _ if app.is_agenda_open => Block::bordered().title(Util::get_spaced_title("My Day")),
             _ => Block::bordered().title(Util::get_spaced_title(&Project::get_current(app).title)),
         };
 
@@ -104,6 +249,8 @@ impl View {
 
         if app.view_mode == ViewMode::ChangeStatusTask
             || app.view_mode == ViewMode::ChangePriorityTask
+            || app.view_mode == ViewMode::ChangeBlockersTask
+            || app.view_mode == ViewMode::MoveTasks
         {
             f.render_widget(items, area)
         } else {
@@ -111,21 +258,404 @@ impl View {
         }
     }
 
+    pub fn show_task_history_modal(app: &mut App, f: &mut Frame, area: Rect) {
+        let task = Task::get_current(app).clone();
+
+        // Newest first
+        let lines: Vec<Line> = task
+            .history
+            .iter()
+            .rev()
+            .map(|event| {
+                let at = event.at.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+
+                Line::from(
+                    [
+                        vec![Span::styled(
+                            format!("{at}  "),
+                            Style::new().fg(Color::DarkGray),
+                        )],
+                        event.change.get_repr(),
+                    ]
+                    .concat(),
+                )
+            })
+            .collect();
+
+        let lines = if lines.is_empty() {
+            vec![Line::raw("No changes recorded")]
+        } else {
+            lines
+        };
+
+        let widget = Paragraph::new(lines).block(
+            Block::bordered().title(Util::get_spaced_title(&format!("History: {}", task.title))),
+        );
+
+        Ui::create_modal(f, 60, 15, area, widget)
+    }
+
+    pub fn show_focus(app: &mut App, f: &mut Frame, area: Rect) {
+        let task = Task::get_current(app).clone();
+        let Some(focus) = &app.focus else {
+            return;
+        };
+
+        let block = Block::bordered().title(Util::get_spaced_title("Focus"));
+        let inner_area = block.inner(area);
+        f.render_widget(block, area);
+
+        let [_, title_area, phase_area, gauge_area, sessions_area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(2),
+            Constraint::Length(2),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner_area);
+        let [_, gauge_area, _] = Layout::horizontal([
+            Constraint::Percentage(25),
+            Constraint::Percentage(50),
+            Constraint::Percentage(25),
+        ])
+        .areas(gauge_area);
+
+        f.render_widget(
+            Paragraph::new(task.title.clone())
+                .style(Style::new().add_modifier(Modifier::BOLD))
+                .centered(),
+            title_area,
+        );
+
+        let (phase, color) = match focus.phase {
+            FocusPhase::Work => ("Focus", Color::LightMagenta),
+            FocusPhase::Break => ("Break", Color::LightGreen),
+            FocusPhase::Idle => ("Break is over, <Space> to start a new session", Color::Gray),
+        };
+
+        f.render_widget(
+            Paragraph::new(phase)
+                .style(Style::new().fg(color))
+                .centered(),
+            phase_area,
+        );
+
+        if focus.phase != FocusPhase::Idle {
+            let duration = focus.get_duration(&app.config.focus);
+            let remaining = focus.get_remaining(&app.config.focus);
+            let ratio = if duration.is_zero() {
+                1.0
+            } else {
+                1.0 - remaining.num_seconds() as f64 / duration.num_seconds() as f64
+            };
+
+            f.render_widget(
+                Gauge::default()
+                    .block(Block::bordered())
+                    .gauge_style(Style::new().fg(color))
+                    .ratio(ratio.clamp(0.0, 1.0))
+                    .label(Util::get_clock_repr(remaining)),
+                gauge_area,
+            );
+        }
+
+        let today = Local::now().date_naive();
+        let sessions_today = task
+            .focus_sessions
+            .iter()
+            .filter(|s| s.start.with_timezone(&Local).date_naive() == today)
+            .count();
+
+        f.render_widget(
+            Paragraph::new(format!(
+                "Sessions today: {sessions_today} - total: {}",
+                task.focus_sessions.len()
+            ))
+            .style(Style::new().fg(Color::DarkGray))
+            .centered(),
+            sessions_area,
+        );
+    }
+
+    pub fn show_calendar(app: &mut App, f: &mut Frame, area: Rect) {
+        let date = app.calendar_date;
+        let today = Local::now().date_naive();
+        let weeks = Calendar::get_weeks(date);
+
+        let block =
+            Block::bordered().title(Util::get_spaced_title(&date.format("%B %Y").to_string()));
+        let inner_area = block.inner(area);
+        f.render_widget(block, area);
+
+        // Header plus two lines per week
+        let [grid_area, tasks_area] = Layout::vertical([
+            Constraint::Length(weeks.len() as u16 * 2 + 2),
+            Constraint::Min(0),
+        ])
+        .areas(inner_area);
+
+        let rows: Vec<Row> = weeks
+            .iter()
+            .map(|week| {
+                let cells = week.iter().map(|day| {
+                    let Some(day) = day else {
+                        return Cell::from("");
+                    };
+
+                    let mut style = Style::new();
+
+                    if *day == today {
+                        style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                    }
+
+                    if *day == date {
+                        style = style.add_modifier(Modifier::REVERSED);
+                    }
+
+                    // Tasks due on the day of each status, in its colour
+                    let counts: Vec<Span> = Calendar::get_status_counts(&app.projects, *day)
+                        .into_iter()
+                        .filter(|(_, count)| *count > 0)
+                        .map(|(status, count)| {
+                            Span::styled(
+                                format!("{count} "),
+                                Style::new().fg(Task::get_status_color(&status.to_string())),
+                            )
+                        })
+                        .collect();
+
+                    Cell::from(Text::from(vec![
+                        Line::styled(format!("{:>2}", day.format("%-d")), style),
+                        Line::from(counts),
+                    ]))
+                });
+
+                Row::new(cells).height(2)
+            })
+            .collect();
+
+        let header = Row::new(["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"])
+            .style(Style::new().fg(Color::DarkGray))
+            .bottom_margin(1);
+
+        f.render_widget(
+            Table::new(rows, [Constraint::Ratio(1, 7); 7]).header(header),
+            grid_area,
+        );
+
+        // Tasks of the selected day
+        let tasks = Calendar::get_tasks(&app.projects, date);
+
+        let lines: Vec<Line> = if tasks.is_empty() {
+            vec![Line::styled(
+                "No tasks due",
+                Style::new().fg(Color::DarkGray),
+            )]
+        } else {
+            tasks
+                .into_iter()
+                .map(|(project, task)| {
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{}: ", project.title),
+                            Style::new().fg(Color::DarkGray),
+                        ),
+                        Span::styled(
+                            format!("[{}] ", task.status),
+                            Style::new().fg(Task::get_status_color(&task.status)),
+                        ),
+                        Span::raw(task.title.clone()),
+                    ])
+                })
+                .collect()
+        };
+
+        f.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(Util::get_spaced_title(&format!(
+                "Due on {}",
+                date.format("%A %d %B")
+            )))),
+            tasks_area,
+        );
+    }
+
+    pub fn show_statistics(app: &mut App, f: &mut Frame, area: Rect) {
+        let statistics = Statistics::new(&app.projects);
+
+        let block = Block::bordered().title(Util::get_spaced_title("Statistics"));
+        let inner_area = block.inner(area);
+        f.render_widget(block, area);
+
+        let [charts_area, projects_area] =
+            Layout::vertical([Constraint::Length(12), Constraint::Min(0)]).areas(inner_area);
+        let [status_area, priority_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(charts_area);
+        let [progress_area, side_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(projects_area);
+        let [largest_area, throughput_area] =
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(side_area);
+
+        // Tasks per status
+        let status_bars: Vec<Bar> = statistics
+            .status_counts
+            .iter()
+            .map(|(status, count)| {
+                Bar::default()
+                    .value(*count)
+                    .label(Line::from(*status))
+                    .style(Style::new().fg(Task::get_status_color(&status.to_string())))
+            })
+            .collect();
+
+        f.render_widget(
+            BarChart::default()
+                .block(Block::bordered().title(Util::get_spaced_title("Tasks per status")))
+                .bar_width(9)
+                .bar_gap(2)
+                .data(BarGroup::default().bars(&status_bars)),
+            status_area,
+        );
+
+        // Open tasks per priority
+        let priority_bars: Vec<Bar> = statistics
+            .priority_counts
+            .iter()
+            .map(|(priority, count)| {
+                let label = match Util::get_priority_indicator(*priority) {
+                    indicator if indicator.is_empty() => "None".to_string(),
+                    indicator => indicator,
+                };
+
+                Bar::default()
+                    .value(*count)
+                    .label(Line::from(label))
+                    .style(Style::new().fg(Color::Red))
+            })
+            .collect();
+
+        f.render_widget(
+            BarChart::default()
+                .block(Block::bordered().title(Util::get_spaced_title("Open tasks per priority")))
+                .bar_width(6)
+                .bar_gap(2)
+                .data(BarGroup::default().bars(&priority_bars)),
+            priority_area,
+        );
+
+        // Completion per project, one gauge per line
+        let progress_block = Block::bordered().title(Util::get_spaced_title("Completion"));
+        let progress_inner_area = progress_block.inner(progress_area);
+        f.render_widget(progress_block, progress_area);
+
+        let gauge_areas = Layout::vertical(
+            statistics
+                .projects_progress
+                .iter()
+                .map(|_| Constraint::Length(1)),
+        )
+        .split(progress_inner_area);
+
+        for ((title, done, total, percentage), gauge_area) in
+            statistics.projects_progress.iter().zip(gauge_areas.iter())
+        {
+            f.render_widget(
+                Gauge::default()
+                    .gauge_style(
+                        Style::new().fg(Project::get_indicator_done_tasks_color(*percentage)),
+                    )
+                    .percent(*percentage as u16)
+                    .label(format!("{title} [{done}/{total}]")),
+                *gauge_area,
+            );
+        }
+
+        // Largest open projects
+        let largest_bars: Vec<Bar> = statistics
+            .largest_open_projects
+            .iter()
+            .map(|(title, open)| {
+                Bar::default()
+                    .value(*open)
+                    .label(Line::from(title.clone()))
+                    .style(Style::new().fg(Color::LightMagenta))
+            })
+            .collect();
+
+        f.render_widget(
+            BarChart::default()
+                .block(Block::bordered().title(Util::get_spaced_title("Largest open projects")))
+                .direction(Direction::Horizontal)
+                .bar_width(1)
+                .bar_gap(1)
+                .data(BarGroup::default().bars(&largest_bars)),
+            largest_area,
+        );
+
+        // Throughput of the last weeks
+        let throughput_bars: Vec<Bar> = statistics
+            .weekly_done
+            .iter()
+            .map(|(week, count)| {
+                Bar::default()
+                    .value(*count)
+                    .label(Line::from(week.clone()))
+                    .style(Style::new().fg(Color::LightGreen))
+            })
+            .collect();
+
+        let get_average_repr = |average: Option<TimeDelta>| match average {
+            Some(average) => Util::get_duration_repr(average),
+            None => "-".to_string(),
+        };
+
+        f.render_widget(
+            BarChart::default()
+                .block(Block::bordered().title(Util::get_spaced_title(&format!(
+                    "Done per week - average lead time {} - cycle time {}",
+                    get_average_repr(statistics.average_lead_time),
+                    get_average_repr(statistics.average_cycle_time)
+                ))))
+                .bar_width(5)
+                .bar_gap(2)
+                .data(BarGroup::default().bars(&throughput_bars)),
+            throughput_area,
+        );
+    }
+
     pub fn show_footer_helper(app: &mut App, f: &mut Frame, area: Rect) {
         let help_string = match app.view_mode {
             ViewMode::ViewProjects => {
-                "<Up/Down k/j> next/prev - <Enter/Right/l> go to tasks - <n> new - <r> rename - <d> delete - <q> quit"
+                "<Up/Down k/j> next/prev - <Enter/Right/l> go to tasks - <n> new - <r> rename - <d> delete - <e> export - <s> statistics - <a> my day - <c> calendar - <q> quit"
+            }
+            ViewMode::ViewStatistics => "<Esc/s> go to projects - <q> quit",
+            ViewMode::ViewCalendar => {
+                "<Left/Right h/l> prev/next day - <Up/Down k/j> prev/next week - <[/]> prev/next month - <t> today - <Esc/c> go to projects - <q> quit"
+            }
+            ViewMode::ViewAgenda => {
+                "<Up/Down k/j> next/prev - <Esc/a> go to projects - <Enter> change status - <p> change priority - <r> rename - <q> quit"
             }
             ViewMode::RenameProject => "<Enter> confirm - <Esc> cancel",
             ViewMode::AddProject => "<Enter> confirm - <Esc> cancel",
             ViewMode::DeleteProject => "<y> confirm - <n> cancel",
 
             ViewMode::ViewTasks => {
-                "<Up/Down k/j> next/prev - <Esc/Left/h> go to projects - <Enter> change status - <p> change priority - <n> new - <r> rename - <d> delete - <q> quit"
+                "<Up/Down k/j> next/prev - <Esc/Left/h> go to projects - <Enter> change status - <p> change priority - <n> new - <r> rename - <D> due date - <R> repeat - <b> blocked by - <B> dependencies - <Space/V> mark/mark range - <m> move - <A> archive - <d> delete - <t> start/stop timer - <f> focus - <H> history - <e> export - <q> quit"
             }
+            ViewMode::ViewTaskHistory => "<Esc/H> close - <q> quit",
+            ViewMode::FocusTask => "<Space> restart session - <Esc/f> leave - <q> quit",
             ViewMode::RenameTask => "<Enter> confirm - <Esc> cancel",
             ViewMode::ChangeStatusTask => "<Up/Down k/j> next/prev - <Enter> confirm - <Esc> cancel",
             ViewMode::ChangePriorityTask => "<Up/Down k/j> next/prev - <Enter> confirm - <Esc> cancel",
+            ViewMode::ChangeBlockersTask => "<Up/Down k/j> next/prev - <Enter/Space> link/unlink - <Esc/b> close",
+            ViewMode::ViewTaskDependencies => "<Esc/B> close - <q> quit",
+            ViewMode::MoveTasks => "<Up/Down k/j> next/prev - <Enter> confirm - <Esc> cancel",
+            ViewMode::ArchiveTasks => "<y> confirm - <n> cancel",
+            ViewMode::ChangeDueDateTask => "<Enter> confirm - <Esc> cancel",
+            ViewMode::ChangeRecurrenceTask => "<Enter> confirm - <Esc> cancel",
             ViewMode::AddTask => "<Enter> confirm - <Esc> cancel",
             ViewMode::DeleteTask => "<y> confirm - <n> cancel",
             ViewMode::InfoMigration => ""